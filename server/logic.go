@@ -27,6 +27,8 @@ type MoveResponse struct {
 	Status  string `json:"status"`
 	Message string `json:"message,omitempty"`
 	NewFEN  string `json:"newFEN"`
+	// PGN result, as GameStatus.outcome in the wasm engine: "1-0", "0-1",
+	// "1/2-1/2", or "*" (chess.NoOutcome) while the game goes on
 	Outcome string `json:"outcome"`
 	Turn    string `json:"turn"`
 }
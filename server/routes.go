@@ -335,7 +335,7 @@ func (s *Server) makeMoveHandler(room *room) http.HandlerFunc {
 				GameID:  gameID,
 				Status:  "ok",
 				NewFEN:  newFen,
-				Outcome: "",
+				Outcome: string(outcome),
 				Turn:    game.Position().Turn().Name(),
 			}
 
@@ -0,0 +1,239 @@
+use std::time::Duration;
+
+use serde::ser::{Serialize, SerializeStruct, Serializer};
+#[cfg(feature = "wasm")]
+use wasm_bindgen::prelude::*;
+
+use crate::tree::GameTree;
+
+/// An engine evaluation from White's point of view, as in `[%eval 0.17]` or `[%eval #-3]`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Eval {
+    Centipawns(i32),
+    /// Mate in this many moves; negative when Black mates.
+    Mate(i32),
+}
+
+impl Eval {
+    /// Reads the argument of an `[%eval]` command. A search depth after a comma is dropped.
+    pub fn parse(text: &str) -> Option<Eval> {
+        let value = text.split(',').next()?.trim();
+        if let Some(moves) = value.strip_prefix('#') {
+            return moves.parse().ok().map(Eval::Mate);
+        }
+        let pawns: f64 = value.parse().ok()?;
+        if !pawns.is_finite() {
+            return None;
+        }
+        Some(Eval::Centipawns((pawns * 100.0).round() as i32))
+    }
+}
+
+impl std::fmt::Display for Eval {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Eval::Centipawns(cp) => write!(f, "{:.2}", *cp as f64 / 100.0),
+            Eval::Mate(moves) => write!(f, "#{}", moves),
+        }
+    }
+}
+
+/// What a person or engine has to say about a move: a comment, NAGs such as
+/// $1 for "!" or $18 for "+-", and the clock and evaluation commands that live
+/// inside PGN comments.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Annotation {
+    /// The comment text without its `[%clk]` and `[%eval]` commands. Any "}" becomes
+    /// ")" when written to PGN, see `pgn_comment`.
+    pub comment: Option<String>,
+    pub nags: Vec<u8>,
+    /// Time left on the mover's clock after the move.
+    pub clock: Option<Duration>,
+    pub eval: Option<Eval>,
+}
+
+impl Annotation {
+    pub fn is_empty(&self) -> bool {
+        *self == Annotation::default()
+    }
+
+    /// Adds a PGN comment, taking out the commands it carries. Text is appended to
+    /// any comment already there.
+    pub fn add_comment(&mut self, text: &str) {
+        let mut rest = String::new();
+        let mut text = text;
+        while let Some(start) = text.find("[%") {
+            let end = match text[start..].find(']') {
+                Some(end) => start + end,
+                None => break,
+            };
+            let command = &text[start + 2..end];
+            let (name, args) = command.split_once(char::is_whitespace).unwrap_or((command, ""));
+            let known = match name {
+                "clk" => parse_clock(args.trim()).map(|clock| self.clock = Some(clock)).is_some(),
+                "eval" => Eval::parse(args).map(|eval| self.eval = Some(eval)).is_some(),
+                _ => false,
+            };
+            rest.push_str(&text[..start]);
+            if !known {
+                rest.push_str(&text[start..=end]);
+            }
+            text = &text[end + 1..];
+        }
+        rest.push_str(text);
+
+        let words: Vec<&str> = self.comment.iter().flat_map(|c| c.split_whitespace()).chain(rest.split_whitespace()).collect();
+        self.comment = if words.is_empty() { None } else { Some(words.join(" ")) };
+    }
+
+    /// The comment as written in PGN, commands first, or `None` if there's nothing to write.
+    /// PGN has no escape inside a comment, so a "}" in the text is written as ")" and
+    /// reads back as one. Clocks keep their milliseconds, as in `[%clk 0:00:05.25]`.
+    pub fn pgn_comment(&self) -> Option<String> {
+        let mut parts = Vec::new();
+        if let Some(eval) = self.eval {
+            parts.push(format!("[%eval {}]", eval));
+        }
+        if let Some(clock) = self.clock {
+            parts.push(format!("[%clk {}]", format_clock(clock)));
+        }
+        if let Some(comment) = &self.comment {
+            parts.push(comment.replace('}', ")"));
+        }
+        if parts.is_empty() { None } else { Some(format!("{{{}}}", parts.join(" "))) }
+    }
+}
+
+// Serialized as {"comment": "...", "nags": [1], "clock": 312.5, "eval": "0.17"}
+impl Serialize for Annotation {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut state = serializer.serialize_struct("Annotation", 4)?;
+        state.serialize_field("comment", &self.comment)?;
+        state.serialize_field("nags", &self.nags)?;
+        state.serialize_field("clock", &self.clock.map(|clock| clock.as_secs_f64()))?;
+        state.serialize_field("eval", &self.eval.map(|eval| eval.to_string()))?;
+        state.end()
+    }
+}
+
+/// The NAG a move suffix or a standalone glyph stands for, such as "!?" or "+-".
+pub fn nag_for_glyph(glyph: &str) -> Option<u8> {
+    let nag = match glyph {
+        "!" => 1,
+        "?" => 2,
+        "!!" => 3,
+        "??" => 4,
+        "!?" => 5,
+        "?!" => 6,
+        "=" => 10,
+        "∞" => 13,
+        "+=" | "⩲" => 14,
+        "=+" | "⩱" => 15,
+        "+/-" | "±" => 16,
+        "-/+" | "∓" => 17,
+        "+-" => 18,
+        "-+" => 19,
+        _ => return None,
+    };
+    Some(nag)
+}
+
+/// "H:MM:SS", with fractions of a second if any.
+fn parse_clock(text: &str) -> Option<Duration> {
+    let mut seconds = 0.0;
+    for part in text.split(':') {
+        let value: f64 = part.parse().ok()?;
+        seconds = seconds * 60.0 + value;
+    }
+    seconds_to_duration(seconds)
+}
+
+fn seconds_to_duration(seconds: f64) -> Option<Duration> {
+    let millis = (seconds * 1000.0).round();
+    if millis.is_finite() && millis >= 0.0 && millis <= u64::MAX as f64 {
+        Some(Duration::from_millis(millis as u64))
+    } else {
+        None
+    }
+}
+
+fn format_clock(clock: Duration) -> String {
+    let secs = clock.as_secs();
+    let mut text = format!("{}:{:02}:{:02}", secs / 3600, secs / 60 % 60, secs % 60);
+    let millis = clock.subsec_millis();
+    if millis > 0 {
+        text.push_str(format!(".{:03}", millis).trim_end_matches('0'));
+    }
+    text
+}
+
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+impl GameTree {
+    /// The annotation on node `id` serialized as JSON, see `Annotation`.
+    pub fn annotation_json(&self, id: usize) -> String {
+        match serde_json::to_string(&self.node(id).map(|node| node.annotation())) {
+            Ok(json) => json,
+            Err(e) => format!("{{\"error\": \"{}\"}}", e),
+        }
+    }
+
+    /// Replaces the comment on node `id`; an empty text removes it.
+    /// Returns false if there is no such node.
+    pub fn set_comment(&mut self, id: usize, text: &str) -> bool {
+        self.edit_annotation(id, |annotation| {
+            annotation.comment = None;
+            annotation.add_comment(text);
+        })
+    }
+
+    /// Adds a NAG to node `id` unless it is already there.
+    pub fn add_nag(&mut self, id: usize, nag: u8) -> bool {
+        self.edit_annotation(id, |annotation| {
+            if !annotation.nags.contains(&nag) {
+                annotation.nags.push(nag);
+            }
+        })
+    }
+
+    pub fn remove_nag(&mut self, id: usize, nag: u8) -> bool {
+        self.edit_annotation(id, |annotation| annotation.nags.retain(|&n| n != nag))
+    }
+
+    /// Sets the clock after the move at node `id`, in seconds; `None` removes it. Returns
+    /// false if there is no such node or the time is negative or not a number.
+    pub fn set_clock(&mut self, id: usize, seconds: Option<f64>) -> bool {
+        let clock = match seconds {
+            None => None,
+            Some(seconds) => match seconds_to_duration(seconds) {
+                Some(clock) => Some(clock),
+                None => return false,
+            },
+        };
+        self.edit_annotation(id, |annotation| annotation.clock = clock)
+    }
+
+    /// Sets the evaluation at node `id` from its PGN form, "0.17" or "#-3"; an empty
+    /// text removes it. Returns false if there is no such node or the text isn't an evaluation.
+    pub fn set_eval(&mut self, id: usize, eval: &str) -> bool {
+        let eval = match eval.trim() {
+            "" => None,
+            text => match Eval::parse(text) {
+                Some(eval) => Some(eval),
+                None => return false,
+            },
+        };
+        self.edit_annotation(id, |annotation| annotation.eval = eval)
+    }
+}
+
+impl GameTree {
+    fn edit_annotation(&mut self, id: usize, edit: impl FnOnce(&mut Annotation)) -> bool {
+        match self.annotation_mut(id) {
+            Some(annotation) => {
+                edit(annotation);
+                true
+            }
+            None => false,
+        }
+    }
+}
@@ -0,0 +1,126 @@
+// Squares are indexed as `x * 8 + y`, matching the (x, y) board coordinates used
+// everywhere else: index 0 is a8, index 7 is h8 and index 63 is h1.
+// Every rank therefore lives in its own byte of the u64.
+
+pub type Bitboard = u64;
+
+pub const EMPTY: Bitboard = 0;
+
+/// The light squares; a8 and h1 are light.
+pub const LIGHT_SQUARES: Bitboard = 0x55AA_55AA_55AA_55AA;
+
+pub const fn bit(sq: usize) -> Bitboard {
+    1u64 << sq
+}
+
+/// Iterates over the squares of a bitboard from a8 towards h1.
+pub fn squares(mut bb: Bitboard) -> impl Iterator<Item = usize> {
+    std::iter::from_fn(move || {
+        if bb == EMPTY {
+            return None;
+        }
+        let sq = bb.trailing_zeros() as usize;
+        bb &= bb - 1;
+        Some(sq)
+    })
+}
+
+const KNIGHT_DELTAS: [(i32, i32); 8] = [(-2, -1), (-2, 1), (-1, -2), (-1, 2), (1, -2), (1, 2), (2, -1), (2, 1)];
+const KING_DELTAS: [(i32, i32); 8] = [(-1, -1), (-1, 0), (-1, 1), (0, -1), (0, 1), (1, -1), (1, 0), (1, 1)];
+// White pawns move towards x = 0, black pawns towards x = 7.
+const WHITE_PAWN_DELTAS: [(i32, i32); 2] = [(-1, -1), (-1, 1)];
+const BLACK_PAWN_DELTAS: [(i32, i32); 2] = [(1, -1), (1, 1)];
+
+const fn leaper_attacks(sq: usize, deltas: &[(i32, i32)]) -> Bitboard {
+    let x = (sq / 8) as i32;
+    let y = (sq % 8) as i32;
+    let mut attacks = EMPTY;
+    let mut i = 0;
+    while i < deltas.len() {
+        let (dx, dy) = deltas[i];
+        let (nx, ny) = (x + dx, y + dy);
+        if nx >= 0 && nx < 8 && ny >= 0 && ny < 8 {
+            attacks |= bit((nx * 8 + ny) as usize);
+        }
+        i += 1;
+    }
+    attacks
+}
+
+const fn leaper_table(deltas: &[(i32, i32)]) -> [Bitboard; 64] {
+    let mut table = [EMPTY; 64];
+    let mut sq = 0;
+    while sq < 64 {
+        table[sq] = leaper_attacks(sq, deltas);
+        sq += 1;
+    }
+    table
+}
+
+/// Mask of every square on the line through `sq` in direction (dx, dy), including `sq` itself.
+const fn line_mask(sq: usize, dx: i32, dy: i32) -> Bitboard {
+    let mut mask = bit(sq);
+    let mut dir = -1;
+    while dir <= 1 {
+        let mut x = (sq / 8) as i32 + dx * dir;
+        let mut y = (sq % 8) as i32 + dy * dir;
+        while x >= 0 && x < 8 && y >= 0 && y < 8 {
+            mask |= bit((x * 8 + y) as usize);
+            x += dx * dir;
+            y += dy * dir;
+        }
+        dir += 2;
+    }
+    mask
+}
+
+const fn line_table(dx: i32, dy: i32) -> [Bitboard; 64] {
+    let mut table = [EMPTY; 64];
+    let mut sq = 0;
+    while sq < 64 {
+        table[sq] = line_mask(sq, dx, dy);
+        sq += 1;
+    }
+    table
+}
+
+pub static KNIGHT_ATTACKS: [Bitboard; 64] = leaper_table(&KNIGHT_DELTAS);
+pub static KING_ATTACKS: [Bitboard; 64] = leaper_table(&KING_DELTAS);
+/// Squares attacked by a pawn, indexed by `[Color::index()][square]`.
+pub static PAWN_ATTACKS: [[Bitboard; 64]; 2] = [leaper_table(&WHITE_PAWN_DELTAS), leaper_table(&BLACK_PAWN_DELTAS)];
+
+static FILE_MASKS: [Bitboard; 64] = line_table(1, 0);
+static DIAGONAL_MASKS: [Bitboard; 64] = line_table(1, 1);
+static ANTI_DIAGONAL_MASKS: [Bitboard; 64] = line_table(1, -1);
+
+/// Hyperbola quintessence along a line with at most one square per rank.
+/// Reversing the bitboard is then just a byte swap.
+fn line_attacks(occupied: Bitboard, sq: usize, mask: Bitboard) -> Bitboard {
+    let slider = bit(sq);
+    let o = occupied & mask;
+    let forward = o.wrapping_sub(slider.wrapping_mul(2));
+    let reverse = o.swap_bytes().wrapping_sub(slider.swap_bytes().wrapping_mul(2)).swap_bytes();
+    (forward ^ reverse) & mask & !slider
+}
+
+/// Same trick for ranks, done on the single byte that holds the rank.
+fn rank_attacks(occupied: Bitboard, sq: usize) -> Bitboard {
+    let shift = sq / 8 * 8;
+    let slider = 1u8 << (sq % 8);
+    let o = (occupied >> shift) as u8;
+    let forward = o.wrapping_sub(slider.wrapping_mul(2));
+    let reverse = o.reverse_bits().wrapping_sub(slider.reverse_bits().wrapping_mul(2)).reverse_bits();
+    (((forward ^ reverse) & !slider) as Bitboard) << shift
+}
+
+pub fn rook_attacks(sq: usize, occupied: Bitboard) -> Bitboard {
+    line_attacks(occupied, sq, FILE_MASKS[sq]) | rank_attacks(occupied, sq)
+}
+
+pub fn bishop_attacks(sq: usize, occupied: Bitboard) -> Bitboard {
+    line_attacks(occupied, sq, DIAGONAL_MASKS[sq]) | line_attacks(occupied, sq, ANTI_DIAGONAL_MASKS[sq])
+}
+
+pub fn queen_attacks(sq: usize, occupied: Bitboard) -> Bitboard {
+    rook_attacks(sq, occupied) | bishop_attacks(sq, occupied)
+}
@@ -0,0 +1,86 @@
+use crate::bitboard::{self, Bitboard, EMPTY, bit};
+use crate::types::{Color, Piece, PieceKind, Square};
+
+/// Piece placement stored as one bitboard per piece kind plus one per color.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct Board {
+    // Indexed by PieceKind (pawn, rook, knight, bishop, queen, king)
+    pieces: [Bitboard; 6],
+    // Indexed by Color (white, black)
+    colors: [Bitboard; 2],
+}
+
+impl Board {
+    pub fn empty() -> Board {
+        Board { pieces: [EMPTY; 6], colors: [EMPTY; 2] }
+    }
+
+    pub fn occupied(&self) -> Bitboard {
+        self.colors[0] | self.colors[1]
+    }
+
+    pub fn by_color(&self, color: Color) -> Bitboard {
+        self.colors[color.index()]
+    }
+
+    /// Pieces of the given kind, both colors.
+    pub fn by_kind(&self, kind: PieceKind) -> Bitboard {
+        self.pieces[kind.index()]
+    }
+
+    /// All pieces of the given kind and color.
+    pub fn pieces(&self, kind: PieceKind, color: Color) -> Bitboard {
+        self.pieces[kind.index()] & self.by_color(color)
+    }
+
+    pub fn piece_at(&self, sq: Square) -> Option<Piece> {
+        let mask = bit(sq.index());
+        let color = if self.colors[0] & mask != EMPTY {
+            Color::White
+        } else if self.colors[1] & mask != EMPTY {
+            Color::Black
+        } else {
+            return None;
+        };
+        let kind = PieceKind::ALL.iter().copied().find(|kind| self.pieces[kind.index()] & mask != EMPTY)?;
+        Some(Piece::new(color, kind))
+    }
+
+    /// Places `piece` on `sq`, replacing whatever stood there.
+    pub fn set_piece(&mut self, sq: Square, piece: Piece) {
+        self.remove_piece(sq);
+        let mask = bit(sq.index());
+        self.pieces[piece.kind.index()] |= mask;
+        self.colors[piece.color.index()] |= mask;
+    }
+
+    pub fn remove_piece(&mut self, sq: Square) {
+        let mask = !bit(sq.index());
+        for bb in self.pieces.iter_mut().chain(self.colors.iter_mut()) {
+            *bb &= mask;
+        }
+    }
+
+    pub fn king_square(&self, color: Color) -> Option<Square> {
+        let kings = self.pieces(PieceKind::King, color);
+        if kings == EMPTY { None } else { Some(Square::from_index(kings.trailing_zeros() as usize)) }
+    }
+
+    /// Every piece of either color attacking `sq`, given the occupancy `occupied`.
+    pub fn attackers_to(&self, sq: Square, occupied: Bitboard) -> Bitboard {
+        let index = sq.index();
+        let rooks_queens = self.by_kind(PieceKind::Rook) | self.by_kind(PieceKind::Queen);
+        let bishops_queens = self.by_kind(PieceKind::Bishop) | self.by_kind(PieceKind::Queen);
+        // A white pawn attacks `sq` exactly when a black pawn on `sq` would attack the pawn's square.
+        (bitboard::PAWN_ATTACKS[Color::Black.index()][index] & self.pieces(PieceKind::Pawn, Color::White))
+            | (bitboard::PAWN_ATTACKS[Color::White.index()][index] & self.pieces(PieceKind::Pawn, Color::Black))
+            | (bitboard::KNIGHT_ATTACKS[index] & self.by_kind(PieceKind::Knight))
+            | (bitboard::KING_ATTACKS[index] & self.by_kind(PieceKind::King))
+            | (bitboard::rook_attacks(index, occupied) & rooks_queens)
+            | (bitboard::bishop_attacks(index, occupied) & bishops_queens)
+    }
+
+    pub fn is_square_attacked(&self, sq: Square, attacker_color: Color) -> bool {
+        self.attackers_to(sq, self.occupied()) & self.by_color(attacker_color) != EMPTY
+    }
+}
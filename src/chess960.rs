@@ -0,0 +1,150 @@
+#[cfg(feature = "wasm")]
+use wasm_bindgen::prelude::*;
+
+use crate::board::Board;
+use crate::error::ChessError;
+use crate::types::{Color, Piece, PieceKind, Square};
+use crate::ChessGame;
+
+/// Where the knights go among the five files left after the bishops and the queen.
+const KNIGHTS: [(usize, usize); 10] = [(0, 1), (0, 2), (0, 3), (0, 4), (1, 2), (1, 3), (1, 4), (2, 3), (2, 4), (3, 4)];
+
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+impl ChessGame {
+    /// A Chess960 game from start position `sp`, 0 to 959, in Scharnagl's numbering.
+    /// Position 518 is the standard setup.
+    pub fn new_chess960(sp: u32) -> Result<ChessGame, ChessError> {
+        let rank = back_rank(sp).ok_or(ChessError::NoSuchStartPosition(sp))?;
+        let white: String = rank.iter().map(|&kind| Piece::new(Color::White, kind).fen_char()).collect();
+        let fen = format!("{}/pppppppp/8/8/8/8/PPPPPPPP/{} w KQkq - 0 1", white.to_lowercase(), white);
+        let mut game = ChessGame::from_fen(&fen)?;
+        game.chess960 = true;
+        Ok(game)
+    }
+
+    /// Whether castling follows the Chess960 rules, with the king moving onto its own rook.
+    pub fn is_chess960(&self) -> bool {
+        self.chess960
+    }
+
+    /// The position as a Shredder-FEN, which names the castling rooks by file, as in "HAha".
+    pub fn shredder_fen(&self) -> Result<String, ChessError> {
+        self.write_fen(true)
+    }
+}
+
+/// The back rank of Chess960 start position `sp`, from the a-file to the h-file.
+fn back_rank(sp: u32) -> Option<[PieceKind; 8]> {
+    if sp >= 960 {
+        return None;
+    }
+    let mut rank: [Option<PieceKind>; 8] = [None; 8];
+    let n = sp as usize;
+    rank[n % 4 * 2 + 1] = Some(PieceKind::Bishop);
+    rank[n / 4 % 4 * 2] = Some(PieceKind::Bishop);
+    let n = n / 16;
+
+    let mut place = |nth_empty: usize, kind: PieceKind| {
+        let file = (0..8).filter(|&y| rank[y].is_none()).nth(nth_empty).unwrap();
+        rank[file] = Some(kind);
+    };
+    place(n % 6, PieceKind::Queen);
+    let (first, second) = KNIGHTS[n / 6];
+    // The second knight goes in after the first one took a square
+    place(first, PieceKind::Knight);
+    place(second - 1, PieceKind::Knight);
+    for &kind in &[PieceKind::Rook, PieceKind::King, PieceKind::Rook] {
+        place(0, kind);
+    }
+
+    Some(rank.map(Option::unwrap))
+}
+
+/// Index of a castling right: white kingside, white queenside, black kingside, black queenside.
+pub(crate) fn castling_index(color: Color, kingside: bool) -> usize {
+    color.index() * 2 + if kingside { 0 } else { 1 }
+}
+
+pub(crate) fn home_rank(color: Color) -> usize {
+    if color == Color::White { 7 } else { 0 }
+}
+
+/// Where king and rook end up after castling: the g and f files, or the c and d files.
+pub(crate) fn castled_squares(home_rank: usize, kingside: bool) -> (Square, Square) {
+    let (king, rook) = if kingside { (6, 5) } else { (2, 3) };
+    (Square::new(home_rank, king).unwrap(), Square::new(home_rank, rook).unwrap())
+}
+
+/// File of the rook furthest from the king on one side of it on the home rank, the rook
+/// that K and Q stand for in X-FEN.
+pub(crate) fn outermost_rook(board: &Board, color: Color, king_file: usize, kingside: bool) -> Option<usize> {
+    let home_rank = home_rank(color);
+    let is_rook = |y: &usize| board.piece_at(Square::new(home_rank, *y).unwrap()) == Some(Piece::new(color, PieceKind::Rook));
+    if kingside { (king_file + 1..8).rev().find(is_rook) } else { (0..king_file).find(is_rook) }
+}
+
+impl ChessGame {
+    pub(crate) fn castling_right(&self, index: usize) -> bool {
+        match index {
+            0 => self.white_can_castle_kingside,
+            1 => self.white_can_castle_queenside,
+            2 => self.black_can_castle_kingside,
+            _ => self.black_can_castle_queenside,
+        }
+    }
+
+    pub(crate) fn set_castling_right(&mut self, index: usize, allowed: bool) {
+        match index {
+            0 => self.white_can_castle_kingside = allowed,
+            1 => self.white_can_castle_queenside = allowed,
+            2 => self.black_can_castle_kingside = allowed,
+            _ => self.black_can_castle_queenside = allowed,
+        }
+    }
+
+    /// The castling a king move from `from` to `to` stands for, as the side and the rook's
+    /// square. In standard chess the king steps two squares from the e-file; in Chess960 it
+    /// moves onto its own rook. Says nothing about whether the castling is legal.
+    pub(crate) fn castling_rook(&self, from: Square, to: Square, color: Color) -> Option<(bool, Square)> {
+        let home_rank = home_rank(color);
+        if from.x() != home_rank || to.x() != home_rank {
+            return None;
+        }
+        if self.chess960 {
+            let own_rook = self.board.piece_at(to) == Some(Piece::new(color, PieceKind::Rook));
+            return if own_rook { Some((to.y() > from.y(), to)) } else { None };
+        }
+        if from.y() != 4 || (to.y() != 6 && to.y() != 2) {
+            return None;
+        }
+        let kingside = to.y() == 6;
+        let rook_file = self.castling_rook_files[castling_index(color, kingside)];
+        Some((kingside, Square::new(home_rank, rook_file).unwrap()))
+    }
+
+    /// The castling field of the FEN: X-FEN names a rook by its file only when it isn't
+    /// the outermost one on its side, Shredder-FEN always does.
+    pub(crate) fn castling_field(&self, shredder: bool) -> String {
+        let mut field = String::new();
+        for (index, &letter) in ['K', 'Q', 'k', 'q'].iter().enumerate() {
+            if !self.castling_right(index) {
+                continue;
+            }
+            let color = if index < 2 { Color::White } else { Color::Black };
+            let file = self.castling_rook_files[index];
+            let rook = Piece::new(color, PieceKind::Rook);
+            let mut outer_files = if index % 2 == 0 { file + 1..8 } else { 0..file };
+            let outermost = !outer_files.any(|y| self.board.piece_at(Square::new(home_rank(color), y).unwrap()) == Some(rook));
+            if shredder || (self.chess960 && !outermost) {
+                let file_letter = (b'a' + file as u8) as char;
+                field.push(if color == Color::White { file_letter.to_ascii_uppercase() } else { file_letter });
+            } else {
+                field.push(letter);
+            }
+        }
+        if field.is_empty() {
+            field.push('-');
+        }
+        field
+    }
+}
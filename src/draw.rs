@@ -0,0 +1,119 @@
+#[cfg(feature = "wasm")]
+use wasm_bindgen::prelude::*;
+
+use crate::bitboard::{self, Bitboard, bit, squares, EMPTY, LIGHT_SQUARES};
+use crate::types::{Color, PieceKind};
+use crate::ChessGame;
+
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+impl ChessGame {
+    /// The current position has occurred at least three times, so a draw can be claimed.
+    pub fn is_threefold_repetition(&self) -> bool {
+        self.repetition_count() >= 3
+    }
+
+    /// The current position has occurred at least five times, which ends the game as a draw.
+    pub fn is_fivefold_repetition(&self) -> bool {
+        self.repetition_count() >= 5
+    }
+
+    /// Neither side has the material to checkmate by any sequence of legal moves:
+    /// bare kings, a single minor piece, or only bishops that all stand on one square color.
+    pub fn is_insufficient_material(&self) -> bool {
+        let heavy_or_pawns = self.board.by_kind(PieceKind::Pawn)
+            | self.board.by_kind(PieceKind::Rook)
+            | self.board.by_kind(PieceKind::Queen);
+        if heavy_or_pawns != EMPTY {
+            return false;
+        }
+        let knights = self.board.by_kind(PieceKind::Knight);
+        let bishops = self.board.by_kind(PieceKind::Bishop);
+
+        if knights == EMPTY {
+            return bishops & LIGHT_SQUARES == EMPTY || bishops & !LIGHT_SQUARES == EMPTY;
+        }
+        knights.count_ones() == 1 && bishops == EMPTY
+    }
+
+    /// A position where no sequence of legal moves can lead to checkmate.
+    /// Besides insufficient material this recognizes fully blocked pawn chains: only kings
+    /// and pawns remain, no pawn can ever move again, and neither king can reach a pawn it
+    /// is allowed to capture. Anything less clear-cut is reported as not dead.
+    pub fn is_dead_position(&self) -> bool {
+        self.is_insufficient_material() || self.is_blocked_pawn_position()
+    }
+}
+
+impl ChessGame {
+    /// How often the current position has occurred, counting itself.
+    /// Only positions since the last capture or pawn move can repeat, so the search
+    /// looks back `halfmove_clock` plies at most.
+    fn repetition_count(&self) -> usize {
+        let current = match self.position_history.last() {
+            Some(&key) => key,
+            None => return 1,
+        };
+        self.position_history
+            .iter()
+            .rev()
+            .take(self.halfmove_clock as usize + 1)
+            .step_by(2)
+            .filter(|&&key| key == current)
+            .count()
+    }
+
+    fn is_blocked_pawn_position(&self) -> bool {
+        let occupied = self.board.occupied();
+        let pawns = self.board.by_kind(PieceKind::Pawn);
+        let kings = self.board.by_kind(PieceKind::King);
+        if pawns == EMPTY || pawns | kings != occupied || self.en_passant_target.is_some() {
+            return false;
+        }
+
+        // Every pawn must stand against another pawn and have nothing to capture
+        for color in [Color::White, Color::Black] {
+            let enemy = self.board.by_color(color.opponent());
+            for sq in squares(self.board.pieces(PieceKind::Pawn, color)) {
+                // A pawn on its last rank can't exist in a legal game; treat it as stuck
+                let ahead = if color == Color::White { sq.wrapping_sub(8) } else { sq + 8 };
+                let blocked = ahead >= 64 || pawns & bit(ahead) != EMPTY;
+                let attacks = bitboard::PAWN_ATTACKS[color.index()][sq];
+                if !blocked || attacks & enemy != EMPTY {
+                    return false;
+                }
+            }
+        }
+
+        // With the pawns frozen, only the kings move. They must never reach an undefended enemy pawn.
+        for color in [Color::White, Color::Black] {
+            let enemy_color = color.opponent();
+            let enemy_pawns = self.board.pieces(PieceKind::Pawn, enemy_color);
+            let guarded = Self::pawn_attacks(enemy_pawns, enemy_color);
+            let region = match self.board.king_square(color) {
+                Some(king) => Self::king_region(king.index(), !pawns & !guarded),
+                None => return false,
+            };
+            let reachable_pawns = squares(region).fold(EMPTY, |acc, sq| acc | bitboard::KING_ATTACKS[sq]) & enemy_pawns;
+            if reachable_pawns & !guarded != EMPTY {
+                return false;
+            }
+        }
+        true
+    }
+
+    fn pawn_attacks(pawns: Bitboard, color: Color) -> Bitboard {
+        squares(pawns).fold(EMPTY, |acc, sq| acc | bitboard::PAWN_ATTACKS[color.index()][sq])
+    }
+
+    /// Every square a king on `start` can walk to while staying on `allowed` squares.
+    fn king_region(start: usize, allowed: Bitboard) -> Bitboard {
+        let mut region = bit(start);
+        loop {
+            let grown = squares(region).fold(region, |acc, sq| acc | (bitboard::KING_ATTACKS[sq] & allowed));
+            if grown == region {
+                return region;
+            }
+            region = grown;
+        }
+    }
+}
@@ -0,0 +1,243 @@
+use std::fmt;
+use std::path::Path;
+use std::str::FromStr;
+use std::time::Duration;
+
+#[cfg(feature = "wasm")]
+use wasm_bindgen::prelude::*;
+
+use crate::error::ChessError;
+use crate::fen::FenError;
+use crate::movegen::Move;
+use crate::ChessGame;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EpdError {
+    /// An EPD record starts with the first four FEN fields.
+    MissingFields,
+    InvalidPosition(FenError),
+    UnterminatedString,
+    /// An operand that doesn't fit its opcode: a move that isn't legal, or a number that isn't one.
+    InvalidOperand { opcode: String, operand: String },
+}
+
+impl fmt::Display for EpdError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EpdError::MissingFields => f.write_str("an EPD record needs the first four FEN fields"),
+            EpdError::InvalidPosition(e) => write!(f, "{}", e),
+            EpdError::UnterminatedString => f.write_str("string operand is never closed"),
+            EpdError::InvalidOperand { opcode, operand } => write!(f, "'{}' is not a valid operand for {}", operand, opcode),
+        }
+    }
+}
+
+impl std::error::Error for EpdError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            EpdError::InvalidPosition(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+/// A position with its EPD operations. The common opcodes are read into fields; every
+/// operation, known or not, is also kept in `operations`.
+#[derive(Debug, Clone)]
+pub struct Epd {
+    /// The position, with the clocks from `hmvc` and `fmvn` if given.
+    pub game: ChessGame,
+    /// Opcodes and their operands in the order given, strings unquoted.
+    pub operations: Vec<(String, Vec<String>)>,
+    pub id: Option<String>,
+    /// `bm`: the best moves.
+    pub best_moves: Vec<Move>,
+    /// `am`: moves to avoid.
+    pub avoid_moves: Vec<Move>,
+    /// `c0` to `c9`.
+    pub comments: [Option<String>; 10],
+    /// `dm`: mate in this many moves.
+    pub direct_mate: Option<u32>,
+    /// `acd`: the depth the analysis reached.
+    pub analysis_depth: Option<u32>,
+    /// `ce`: the evaluation in centipawns for the side to move.
+    pub centipawns: Option<i32>,
+}
+
+impl Epd {
+    pub fn parse(epd: &str) -> Result<Epd, EpdError> {
+        let mut rest = epd.trim();
+        let mut fields = Vec::new();
+        for _ in 0..4 {
+            let end = rest.find(char::is_whitespace).unwrap_or(rest.len());
+            if end == 0 {
+                return Err(EpdError::MissingFields);
+            }
+            fields.push(&rest[..end]);
+            rest = rest[end..].trim_start();
+        }
+        let operations = parse_operations(rest)?;
+
+        let operand = |opcode: &str| first_operand(&operations, opcode);
+        let clocks = format!("{} {}", number::<u32>(&operations, "hmvc")?.unwrap_or(0), number::<u32>(&operations, "fmvn")?.unwrap_or(1));
+        let game = ChessGame::from_fen(&format!("{} {}", fields.join(" "), clocks)).map_err(EpdError::InvalidPosition)?;
+
+        let moves = |opcode: &str| -> Result<Vec<Move>, EpdError> {
+            let operands = operations.iter().filter(|(op, _)| op == opcode).flat_map(|(_, operands)| operands);
+            operands
+                .map(|san| game.parse_san(san).map_err(|_| EpdError::InvalidOperand { opcode: opcode.to_string(), operand: san.clone() }))
+                .collect()
+        };
+        let mut comments: [Option<String>; 10] = Default::default();
+        for (i, comment) in comments.iter_mut().enumerate() {
+            *comment = operand(&format!("c{}", i)).cloned();
+        }
+
+        Ok(Epd {
+            id: operand("id").cloned(),
+            best_moves: moves("bm")?,
+            avoid_moves: moves("am")?,
+            comments,
+            direct_mate: number(&operations, "dm")?,
+            analysis_depth: number(&operations, "acd")?,
+            centipawns: number(&operations, "ce")?,
+            game,
+            operations,
+        })
+    }
+
+    /// Runs a test suite, one EPD record per line, searching each position to `depth`
+    /// plies or for `time`. Blank lines and lines starting with `#` are skipped.
+    pub fn run_suite(suite: &str, depth: u32, time: Option<Duration>) -> Vec<SuiteResult> {
+        suite
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| !line.trim().is_empty() && !line.trim_start().starts_with('#'))
+            .map(|(index, line)| SuiteResult::run(index + 1, line, depth, time))
+            .collect()
+    }
+
+    /// `run_suite` on the contents of an `.epd` file.
+    pub fn run_suite_file<P: AsRef<Path>>(path: P, depth: u32, time: Option<Duration>) -> std::io::Result<Vec<SuiteResult>> {
+        Ok(Epd::run_suite(&std::fs::read_to_string(path)?, depth, time))
+    }
+}
+
+fn first_operand<'a>(operations: &'a [(String, Vec<String>)], opcode: &str) -> Option<&'a String> {
+    operations.iter().find(|(op, _)| op == opcode).and_then(|(_, operands)| operands.first())
+}
+
+fn number<T: FromStr>(operations: &[(String, Vec<String>)], opcode: &str) -> Result<Option<T>, EpdError> {
+    first_operand(operations, opcode)
+        .map(|value| value.parse().map_err(|_| EpdError::InvalidOperand { opcode: opcode.to_string(), operand: value.clone() }))
+        .transpose()
+}
+
+/// Operations are an opcode and its operands, each ended by a semicolon.
+fn parse_operations(text: &str) -> Result<Vec<(String, Vec<String>)>, EpdError> {
+    let mut operations = Vec::new();
+    let mut words: Vec<String> = Vec::new();
+    let mut chars = text.chars().peekable();
+    while let Some(&ch) = chars.peek() {
+        match ch {
+            ';' => {
+                chars.next();
+                if !words.is_empty() {
+                    let opcode = words.remove(0);
+                    operations.push((opcode, std::mem::take(&mut words)));
+                }
+            }
+            '"' => {
+                chars.next();
+                let mut string = String::new();
+                loop {
+                    match chars.next() {
+                        Some('"') => break,
+                        Some('\\') => string.extend(chars.next()),
+                        Some(ch) => string.push(ch),
+                        None => return Err(EpdError::UnterminatedString),
+                    }
+                }
+                words.push(string);
+            }
+            _ if ch.is_whitespace() => {
+                chars.next();
+            }
+            _ => {
+                let mut word = String::new();
+                while let Some(&ch) = chars.peek() {
+                    if ch.is_whitespace() || ch == ';' || ch == '"' {
+                        break;
+                    }
+                    word.push(ch);
+                    chars.next();
+                }
+                words.push(word);
+            }
+        }
+    }
+    // The last semicolon is often left out
+    if !words.is_empty() {
+        let opcode = words.remove(0);
+        operations.push((opcode, words));
+    }
+    Ok(operations)
+}
+
+/// How the search did on one record of a test suite.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SuiteResult {
+    /// The line of the suite the record is on, counting from 1.
+    pub line: usize,
+    pub id: Option<String>,
+    /// The move the search chose, in SAN.
+    pub found: Option<String>,
+    /// The `bm` moves in SAN.
+    pub expected: Vec<String>,
+    /// The `am` moves in SAN.
+    pub avoided: Vec<String>,
+    /// The found move is one of the best moves, or avoids the `am` moves if there are none.
+    pub passed: bool,
+    /// Set when the record couldn't be read.
+    pub error: Option<EpdError>,
+}
+
+impl SuiteResult {
+    fn run(line: usize, record: &str, depth: u32, time: Option<Duration>) -> SuiteResult {
+        let epd = match Epd::parse(record) {
+            Ok(epd) => epd,
+            Err(error) => {
+                return SuiteResult { line, id: None, found: None, expected: Vec::new(), avoided: Vec::new(), passed: false, error: Some(error) };
+            }
+        };
+        let found = epd.game.search(depth, time).map(|result| result.best_move);
+        let passed = match found {
+            Some(mv) if !epd.best_moves.is_empty() => epd.best_moves.contains(&mv),
+            Some(mv) => !epd.avoid_moves.contains(&mv),
+            None => false,
+        };
+        let mut position = epd.game.clone();
+        let mut san = |moves: &[Move]| moves.iter().map(|mv| position.san_in_place(mv)).collect();
+        let expected = san(&epd.best_moves);
+        let avoided = san(&epd.avoid_moves);
+        SuiteResult {
+            line,
+            found: found.map(|mv| position.san_in_place(&mv)),
+            expected,
+            avoided,
+            passed,
+            error: None,
+            id: epd.id,
+        }
+    }
+}
+
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+impl ChessGame {
+    /// Loads the position of an EPD record. The clocks come from its `hmvc` and `fmvn`
+    /// operations if present; the other operations are ignored.
+    pub fn load_epd(&mut self, epd: &str) -> Result<(), ChessError> {
+        *self = Epd::parse(epd)?.game;
+        Ok(())
+    }
+}
@@ -0,0 +1,139 @@
+use std::error::Error;
+use std::fmt;
+
+#[cfg(feature = "wasm")]
+use wasm_bindgen::JsValue;
+
+use crate::epd::EpdError;
+use crate::fen::FenError;
+use crate::pgn::PgnError;
+use crate::san::SanError;
+use crate::types::{Color, Square};
+use crate::validation::IllegalMoveReason;
+
+/// Why a promotion request was refused.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PromotionError {
+    /// A promotion piece was given for a move that doesn't reach the last rank with a pawn.
+    NotAPromotion,
+    NonePending,
+    /// A pawn reaching the last rank needs a piece to promote to.
+    MissingPiece,
+    /// The pending promotion is on a different square than the one given.
+    WrongSquare,
+    /// Not a rook, knight, bishop or queen of the promoting side.
+    InvalidPiece,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ChessError {
+    /// The piece on `from` can't legally move to `to`.
+    IllegalMove { from: Square, to: Square, reason: IllegalMoveReason },
+    /// The piece to move belongs to the side not on move; holds the side that is.
+    WrongTurn(Color),
+    EmptySquare(Square),
+    /// Coordinates outside the board.
+    OffBoard { x: usize, y: usize },
+    InvalidFen(FenError),
+    InvalidSan(SanError),
+    InvalidPgn(PgnError),
+    InvalidEpd(EpdError),
+    /// Text that isn't a UCI move like "e2e4" or "e7e8q".
+    InvalidUci(String),
+    InvalidPromotion(PromotionError),
+    /// PGN tags that aren't a JSON object of strings.
+    InvalidTags(String),
+    /// Chess960 start positions are numbered 0 to 959.
+    NoSuchStartPosition(u32),
+    /// Not a piece in the i32 encoding from `constants`.
+    UnknownPiece(i32),
+    /// Not 1 for White or 2 for Black.
+    UnknownColor(i32),
+    /// No double push of the side that just moved can have passed over this square.
+    ImpossibleEnPassant(Square),
+    /// A pawn promotion is waiting for its piece.
+    PromotionPending,
+    GameOver,
+    NoDrawToClaim,
+}
+
+impl fmt::Display for ChessError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ChessError::IllegalMove { from, to, reason } => write!(f, "illegal move from {} to {}: {}", from, to, reason),
+            ChessError::WrongTurn(turn) => write!(f, "not {}'s turn", turn.opponent()),
+            ChessError::EmptySquare(sq) => write!(f, "no piece at starting square {}", sq),
+            ChessError::OffBoard { x, y } => write!(f, "({},{}) is off the board", x, y),
+            ChessError::InvalidFen(e) => write!(f, "{}", e),
+            ChessError::InvalidSan(e) => write!(f, "{}", e),
+            ChessError::InvalidPgn(e) => write!(f, "{}", e),
+            ChessError::InvalidEpd(e) => write!(f, "{}", e),
+            ChessError::InvalidUci(uci) => write!(f, "'{}' is not a UCI move", uci),
+            ChessError::InvalidPromotion(reason) => f.write_str(match reason {
+                PromotionError::NotAPromotion => "only a pawn reaching the last rank can promote",
+                PromotionError::NonePending => "no pawn promotion is pending",
+                PromotionError::MissingPiece => "choose a piece for the promotion",
+                PromotionError::WrongSquare => "the pending promotion is on a different square",
+                PromotionError::InvalidPiece => "invalid piece for promotion",
+            }),
+            ChessError::InvalidTags(e) => write!(f, "invalid PGN tags: {}", e),
+            ChessError::NoSuchStartPosition(sp) => write!(f, "there is no Chess960 start position {}", sp),
+            ChessError::UnknownPiece(code) => write!(f, "{} is not a piece", code),
+            ChessError::UnknownColor(code) => write!(f, "{} is not a color", code),
+            ChessError::ImpossibleEnPassant(sq) => write!(f, "no pawn can just have passed over {}", sq),
+            ChessError::PromotionPending => f.write_str("a pawn promotion is pending, choose a piece first"),
+            ChessError::GameOver => f.write_str("the game is over"),
+            ChessError::NoDrawToClaim => f.write_str("no draw can be claimed in this position"),
+        }
+    }
+}
+
+impl Error for ChessError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            ChessError::InvalidFen(e) => Some(e),
+            ChessError::InvalidSan(e) => Some(e),
+            ChessError::InvalidPgn(e) => Some(e),
+            ChessError::InvalidEpd(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<FenError> for ChessError {
+    fn from(e: FenError) -> ChessError {
+        ChessError::InvalidFen(e)
+    }
+}
+
+impl From<SanError> for ChessError {
+    fn from(e: SanError) -> ChessError {
+        ChessError::InvalidSan(e)
+    }
+}
+
+impl From<PgnError> for ChessError {
+    fn from(e: PgnError) -> ChessError {
+        ChessError::InvalidPgn(e)
+    }
+}
+
+impl From<EpdError> for ChessError {
+    fn from(e: EpdError) -> ChessError {
+        ChessError::InvalidEpd(e)
+    }
+}
+
+impl From<PromotionError> for ChessError {
+    fn from(e: PromotionError) -> ChessError {
+        ChessError::InvalidPromotion(e)
+    }
+}
+
+// Errors cross into JS as their message
+#[cfg(feature = "wasm")]
+impl From<ChessError> for JsValue {
+    fn from(e: ChessError) -> JsValue {
+        JsValue::from_str(&e.to_string())
+    }
+}
@@ -1,37 +1,61 @@
+use std::fmt;
+
+#[cfg(feature = "wasm")]
 use wasm_bindgen::prelude::*;
-use wasm_bindgen::JsValue;
 
-use crate::constants::{
-    W_PAWN, W_ROOK, W_KNIGHT, W_BISHOP, W_QUEEN, W_KING,
-    B_PAWN, B_ROOK, B_KNIGHT, B_BISHOP, B_QUEEN, B_KING,
-    WHITE, BLACK
-};
+use crate::board::Board;
+use crate::chess960::{castling_index, home_rank, outermost_rook};
+use crate::error::ChessError;
+use crate::types::{Color, Piece, PieceKind, Square};
 use crate::ChessGame;
 
-#[wasm_bindgen]
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
 impl ChessGame {
-    #[wasm_bindgen]
-    pub fn fen(&self) -> String {
+    /// The position as a FEN string. Refused while a promotion is pending,
+    /// since the move that reaches it is only half made.
+    #[cfg_attr(feature = "wasm", wasm_bindgen)]
+    pub fn fen(&self) -> Result<String, ChessError> {
+        self.write_fen(false)
+    }
+    /// Replaces the game with the position from `fen`. On error the game is left untouched.
+    /// Castling may be written as in X-FEN or Shredder-FEN; rights that need Chess960
+    /// rules switch them on.
+    #[cfg_attr(feature = "wasm", wasm_bindgen)]
+    pub fn load_fen(&mut self, fen: &str) -> Result<(), ChessError> {
+        let parsed = parse_fen(fen)?;
+        self.set_position(parsed);
+        Ok(())
+    }
+}
+
+impl ChessGame {
+    /// Builds a game from a FEN string.
+    pub fn from_fen(fen: &str) -> Result<ChessGame, FenError> {
+        let mut game = ChessGame::new();
+        game.set_position(parse_fen(fen)?);
+        Ok(game)
+    }
+
+    pub(crate) fn write_fen(&self, shredder: bool) -> Result<String, ChessError> {
+        if self.pending_promotion.is_some() {
+            return Err(ChessError::PromotionPending);
+        }
+
         let mut fen_string = String::new();
 
         // piece Piece Placement
         for r in 0..8 {
             let mut empty_squares = 0;
             for c in 0..8 {
-                let piece = self.board[r][c];
-                if piece == 0 {
-                    empty_squares += 1;
-                } else {
-                    if empty_squares > 0 {
-                        fen_string.push_str(&empty_squares.to_string());
-                        empty_squares = 0;
+                match Square::new(r, c).and_then(|sq| self.piece_at(sq)) {
+                    None => empty_squares += 1,
+                    Some(piece) => {
+                        if empty_squares > 0 {
+                            fen_string.push_str(&empty_squares.to_string());
+                            empty_squares = 0;
+                        }
+                        fen_string.push(piece.fen_char());
                     }
-                    let piece_char = match piece {
-                        W_PAWN => 'P', W_ROOK => 'R', W_KNIGHT => 'N', W_BISHOP => 'B', W_QUEEN => 'Q', W_KING => 'K',
-                        B_PAWN => 'p', B_ROOK => 'r', B_KNIGHT => 'n', B_BISHOP => 'b', B_QUEEN => 'q', B_KING => 'k',
-                        _ => '?',
-                    };
-                    fen_string.push(piece_char);
                 }
             }
             if empty_squares > 0 {
@@ -43,93 +67,306 @@ impl ChessGame {
         }
 
         fen_string.push(' ');
-        fen_string.push(if self.current_turn == WHITE { 'w' } else { 'b' });
+        fen_string.push(if self.current_turn == Color::White { 'w' } else { 'b' });
 
         //  Castling Availability
         fen_string.push(' ');
-        let mut castling_availability = String::new();
-        if self.white_can_castle_kingside { castling_availability.push('K'); }
-        if self.white_can_castle_queenside { castling_availability.push('Q'); }
-        if self.black_can_castle_kingside { castling_availability.push('k'); }
-        if self.black_can_castle_queenside { castling_availability.push('q'); }
-        if castling_availability.is_empty() {
-            fen_string.push('-');
-        } else {
-            fen_string.push_str(&castling_availability);
-        }
+        fen_string.push_str(&self.castling_field(shredder));
 
         // En Passant Target
         fen_string.push(' ');
-        if let Some((r, c)) = self.en_passant_target {
-            let file = (c as u8 + b'a') as char;
-            let rank = 8 - r;
-            fen_string.push(file);
-            fen_string.push_str(&rank.to_string());
+        if let Some(target) = self.en_passant_target {
+            fen_string.push_str(&target.to_string());
         } else {
             fen_string.push('-');
         }
 
-        // Halfmove Clock & 6. Fullmove Number (placeholders)
-        fen_string.push_str(" 0 1");
+        // Halfmove Clock & Fullmove Number
+        fen_string.push_str(&format!(" {} {}", self.halfmove_clock, self.fullmove_number));
 
-        fen_string
+        Ok(fen_string)
     }
-    #[wasm_bindgen]
-pub fn load_fen(&mut self, fen:&str) -> Result<(), JsValue> {
-    let parts: Vec<&str> = fen.split_whitespace().collect();
-    if parts.len() < 4 {
-        return Err(JsValue::from_str("Invalid FEN string"));
 
+    fn set_position(&mut self, parsed: ParsedFen) {
+        self.board = parsed.board;
+        self.current_turn = parsed.turn;
+        let [white_kingside, white_queenside, black_kingside, black_queenside] = parsed.castling;
+        self.white_can_castle_kingside = white_kingside;
+        self.white_can_castle_queenside = white_queenside;
+        self.black_can_castle_kingside = black_kingside;
+        self.black_can_castle_queenside = black_queenside;
+        self.castling_rook_files = parsed.castling_rook_files;
+        self.chess960 = parsed.chess960;
+        self.en_passant_target = parsed.en_passant_target;
+        self.halfmove_clock = parsed.halfmove_clock;
+        self.fullmove_number = parsed.fullmove_number;
+        self.reset_history();
     }
 
-    let mut new_board = vec![vec![0; 8]; 8];
-    let piece_placement = parts[0];
-    let mut row = 0;
-    for rank in piece_placement.split('/') {
-        let mut col = 0;
-        for ch in rank.chars() {
-            if col >= 8 { break; }
-            if let Some(digit) = ch.to_digit(10) {
-                col += digit as usize;
-            } else {
-                let piece = match ch {
-                    'p' => B_PAWN, 'r' => B_ROOK, 'n' => B_KNIGHT, 'b' => B_BISHOP, 'q' => B_QUEEN, 'k' => B_KING,
-                    'P' => W_PAWN, 'R' => W_ROOK, 'N' => W_KNIGHT, 'B' => W_BISHOP, 'Q' => W_QUEEN, 'K' => W_KING,
-                    _ => 0,
-                };
-                if piece != 0 {
-                    new_board[row][col] = piece;
-                }
-                col += 1;
+    // Earlier positions can't be known, so repetition counting starts here
+    pub(crate) fn reset_history(&mut self) {
+        self.position_history = vec![self.position_key()];
+        self.termination = None;
+        self.recorded_result = None;
+        self.pending_promotion = None;
+        self.history.clear();
+        self.redo_stack.clear();
+        self.annotations.clear();
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FenField {
+    Placement,
+    SideToMove,
+    Castling,
+    EnPassant,
+    HalfmoveClock,
+    FullmoveNumber,
+}
+
+impl fmt::Display for FenField {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            FenField::Placement => "piece placement",
+            FenField::SideToMove => "side to move",
+            FenField::Castling => "castling",
+            FenField::EnPassant => "en passant",
+            FenField::HalfmoveClock => "halfmove clock",
+            FenField::FullmoveNumber => "fullmove number",
+        };
+        f.write_str(name)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FenErrorKind {
+    /// A FEN has 6 fields; the two clocks may be left out.
+    WrongFieldCount(usize),
+    WrongRankCount(usize),
+    /// A rank (8 = first rank) describes this many squares instead of 8.
+    BadRankLength { rank: usize, squares: usize },
+    UnknownPiece(char),
+    /// Two digits in a row within a rank, as in "44"; runs of empty squares are one digit.
+    ConsecutiveDigits,
+    InvalidSideToMove(String),
+    InvalidCastling(char),
+    ImpossibleEnPassant(String),
+    NonNumericClock(String),
+    /// The fullmove number starts at 1.
+    ZeroFullmoveNumber,
+}
+
+/// Why a FEN string was rejected. `offset` is the character offset into the FEN string.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FenError {
+    pub field: FenField,
+    pub offset: usize,
+    pub kind: FenErrorKind,
+}
+
+impl fmt::Display for FenError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.kind {
+            FenErrorKind::WrongFieldCount(found) => write!(f, "expected 4 or 6 fields, found {}", found)?,
+            FenErrorKind::WrongRankCount(found) => write!(f, "expected 8 ranks, found {}", found)?,
+            FenErrorKind::BadRankLength { rank, squares } => write!(f, "rank {} has {} squares instead of 8", rank, squares)?,
+            FenErrorKind::UnknownPiece(ch) => write!(f, "unknown piece '{}'", ch)?,
+            FenErrorKind::ConsecutiveDigits => f.write_str("two digits in a row")?,
+            FenErrorKind::InvalidSideToMove(found) => write!(f, "expected 'w' or 'b', found '{}'", found)?,
+            FenErrorKind::InvalidCastling(ch) => write!(f, "unexpected '{}'", ch)?,
+            FenErrorKind::ImpossibleEnPassant(found) => write!(f, "'{}' is not a possible en passant square", found)?,
+            FenErrorKind::NonNumericClock(found) => write!(f, "'{}' is not a number", found)?,
+            FenErrorKind::ZeroFullmoveNumber => f.write_str("the fullmove number can't be 0")?,
+        }
+        write!(f, " in {} field at offset {}", self.field, self.offset)
+    }
+}
+
+impl std::error::Error for FenError {}
+
+struct ParsedFen {
+    board: Board,
+    turn: Color,
+    // White kingside, white queenside, black kingside, black queenside
+    castling: [bool; 4],
+    castling_rook_files: [usize; 4],
+    // Set when the castling rights only make sense under Chess960 rules
+    chess960: bool,
+    en_passant_target: Option<Square>,
+    halfmove_clock: u32,
+    fullmove_number: u32,
+}
+
+/// Splits on runs of whitespace, keeping the character offset where each field starts.
+fn fields_with_offsets(fen: &str) -> Vec<(usize, &str)> {
+    let mut fields = Vec::new();
+    let mut start = None;
+    for (char_offset, (byte, ch)) in fen.char_indices().enumerate() {
+        match (ch.is_whitespace(), start) {
+            (false, None) => start = Some((char_offset, byte)),
+            (true, Some((offset, begin))) => {
+                fields.push((offset, &fen[begin..byte]));
+                start = None;
             }
+            _ => {}
         }
-        row += 1;
-        if row >= 8 { break; }
     }
-    self.board = new_board;
+    if let Some((offset, begin)) = start {
+        fields.push((offset, &fen[begin..]));
+    }
+    fields
+}
+
+fn parse_fen(fen: &str) -> Result<ParsedFen, FenError> {
+    let fields = fields_with_offsets(fen);
+    if fields.len() != 4 && fields.len() != 6 {
+        let offset = fields.get(6).or_else(|| fields.last()).map_or(0, |&(offset, _)| offset);
+        return Err(FenError { field: FenField::Placement, offset, kind: FenErrorKind::WrongFieldCount(fields.len()) });
+    }
 
-    self.current_turn = if parts[1]== "w" { WHITE} else {BLACK};
+    let board = parse_placement(fields[0].0, fields[0].1)?;
 
-    //castling
-    let castling = parts[2];
-    self.white_can_castle_kingside = castling.contains('K');
-    self.white_can_castle_queenside = castling.contains('Q');
-    self.black_can_castle_kingside = castling.contains('k');
-    self.black_can_castle_queenside = castling.contains('q');
+    let (offset, side) = fields[1];
+    let turn = match side {
+        "w" => Color::White,
+        "b" => Color::Black,
+        _ => return Err(FenError { field: FenField::SideToMove, offset, kind: FenErrorKind::InvalidSideToMove(side.to_string()) }),
+    };
+
+    let (offset, castling_field) = fields[2];
+    let (castling, castling_rook_files, chess960) = parse_castling(offset, castling_field, &board)?;
+
+    let (offset, en_passant) = fields[3];
+    let en_passant_target = if en_passant == "-" {
+        None
+    } else {
+        let target = parse_en_passant(en_passant, turn, &board);
+        if target.is_none() {
+            return Err(FenError { field: FenField::EnPassant, offset, kind: FenErrorKind::ImpossibleEnPassant(en_passant.to_string()) });
+        }
+        target
+    };
 
-    let en_passant = parts[3];
-    if en_passant == "-" {
-        self.en_passant_target = None;
+    // The clocks are optional, many tools drop them
+    let (halfmove_clock, fullmove_number) = if fields.len() == 6 {
+        (
+            parse_clock(fields[4], FenField::HalfmoveClock)?,
+            parse_clock(fields[5], FenField::FullmoveNumber)?,
+        )
     } else {
-        let file = en_passant.chars().nth(0).unwrap() as usize - 'a' as usize;
-        let rank = 8 - en_passant.chars().nth(1).unwrap().to_digit(10).unwrap() as usize;
-        self.en_passant_target = Some((rank, file));
+        (0, 1)
+    };
+
+    Ok(ParsedFen { board, turn, castling, castling_rook_files, chess960, en_passant_target, halfmove_clock, fullmove_number })
+}
+
+/// Reads "KQkq" as in X-FEN, where K and Q stand for the outermost rook on that side of
+/// the king, and file letters as in Shredder-FEN, "HAha" or "Bd".
+fn parse_castling(offset: usize, field: &str, board: &Board) -> Result<([bool; 4], [usize; 4], bool), FenError> {
+    let mut castling = [false; 4];
+    let mut rook_files = [7, 0, 7, 0];
+    let mut chess960 = false;
+    if field == "-" {
+        return Ok((castling, rook_files, chess960));
     }
-    // TODO:
-    // Ignoring halfmove clock (parts[4]) and fullmove number (parts[5]) for now
+    for (i, ch) in field.chars().enumerate() {
+        let error = FenError { field: FenField::Castling, offset: offset + i, kind: FenErrorKind::InvalidCastling(ch) };
+        let color = if ch.is_ascii_uppercase() { Color::White } else { Color::Black };
+        let home_rank = home_rank(color);
+        let king_file = board.king_square(color).filter(|king| king.x() == home_rank).map(|king| king.y());
 
-    Ok(())
+        let (kingside, file) = match ch.to_ascii_lowercase() {
+            side @ ('k' | 'q') => {
+                let kingside = side == 'k';
+                let outermost = king_file.and_then(|king| outermost_rook(board, color, king, kingside));
+                (kingside, outermost.unwrap_or(if kingside { 7 } else { 0 }))
+            }
+            letter @ 'a'..='h' => {
+                let file = (letter as u8 - b'a') as usize;
+                match king_file {
+                    Some(king) if king != file => (file > king, file),
+                    _ => return Err(error),
+                }
+            }
+            _ => return Err(error),
+        };
+        let index = castling_index(color, kingside);
+        if castling[index] {
+            return Err(error);
+        }
+        castling[index] = true;
+        rook_files[index] = file;
+        // Only the standard setup castles under the standard rules
+        chess960 |= file != [7, 0, 7, 0][index] || king_file.is_some_and(|king| king != 4);
+    }
+    Ok((castling, rook_files, chess960))
+}
+
+fn parse_placement(offset: usize, placement: &str) -> Result<Board, FenError> {
+    let ranks: Vec<&str> = placement.split('/').collect();
+    if ranks.len() != 8 {
+        return Err(FenError { field: FenField::Placement, offset, kind: FenErrorKind::WrongRankCount(ranks.len()) });
+    }
+
+    let mut board = Board::empty();
+    let mut rank_offset = offset;
+    for (row, rank) in ranks.iter().enumerate() {
+        let mut col = 0;
+        let mut after_digit = false;
+        for (i, ch) in rank.chars().enumerate() {
+            if let Some(digit @ 1..=8) = ch.to_digit(10) {
+                if after_digit {
+                    return Err(FenError { field: FenField::Placement, offset: rank_offset + i, kind: FenErrorKind::ConsecutiveDigits });
+                }
+                after_digit = true;
+                col += digit as usize;
+                continue;
+            }
+            after_digit = false;
+            let piece = match Piece::from_fen_char(ch) {
+                Some(piece) => piece,
+                None => return Err(FenError { field: FenField::Placement, offset: rank_offset + i, kind: FenErrorKind::UnknownPiece(ch) }),
+            };
+            if let Some(sq) = Square::new(row, col) {
+                board.set_piece(sq, piece);
+            }
+            col += 1;
+        }
+        if col != 8 {
+            return Err(FenError { field: FenField::Placement, offset: rank_offset, kind: FenErrorKind::BadRankLength { rank: 8 - row, squares: col } });
+        }
+        // Skip past the rank and its '/'
+        rank_offset += rank.chars().count() + 1;
+    }
+    Ok(board)
+}
 
+/// The en passant square must sit behind a pawn that could just have made a double step:
+/// on the sixth rank with White to move (third with Black), with that pawn in front of it
+/// and the square it came from empty.
+fn parse_en_passant(field: &str, turn: Color, board: &Board) -> Option<Square> {
+    let target = Square::from_algebraic(field)?;
+    let y = target.y();
+    let (x, pawn_x, origin_x) = if turn == Color::White { (2, 3, 1) } else { (5, 4, 6) };
+    let pawn = Piece::new(turn.opponent(), PieceKind::Pawn);
 
+    let plausible = target.x() == x
+        && board.piece_at(Square::new(pawn_x, y)?) == Some(pawn)
+        && board.piece_at(target).is_none()
+        && board.piece_at(Square::new(origin_x, y)?).is_none();
+    if plausible { Some(target) } else { None }
 }
+
+/// Plain digits only: `str::parse` would also take a leading '+'.
+fn parse_clock((offset, field): (usize, &str), which: FenField) -> Result<u32, FenError> {
+    let error = |kind| FenError { field: which, offset, kind };
+    if field.is_empty() || !field.bytes().all(|b| b.is_ascii_digit()) {
+        return Err(error(FenErrorKind::NonNumericClock(field.to_string())));
+    }
+    match field.parse() {
+        Ok(0) if which == FenField::FullmoveNumber => Err(error(FenErrorKind::ZeroFullmoveNumber)),
+        Ok(value) => Ok(value),
+        Err(_) => Err(error(FenErrorKind::NonNumericClock(field.to_string()))),
+    }
 }
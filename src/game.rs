@@ -1,29 +1,36 @@
+#[cfg(feature = "wasm")]
 use wasm_bindgen::prelude::*;
 
-use crate::constants::{
-    W_PAWN, W_ROOK, W_KNIGHT, W_BISHOP, W_QUEEN, W_KING,
-    B_PAWN, B_ROOK, B_KNIGHT, B_BISHOP, B_QUEEN, B_KING,
-    WHITE,
-};
+use crate::board::Board;
+use crate::types::{Color, Piece, PieceKind, Square};
 use crate::ChessGame;
 
-#[wasm_bindgen]
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
 impl ChessGame {
-    #[wasm_bindgen(constructor)]
+    #[cfg_attr(feature = "wasm", wasm_bindgen(constructor))]
     pub fn new() -> ChessGame {
-        let mut board = vec![vec![0; 8]; 8];
-        // Black pieces (Rank 8 -> board index 0)
-        board[0] = vec![B_ROOK,B_KNIGHT, B_BISHOP, B_QUEEN, B_KING, B_BISHOP, B_KNIGHT, B_ROOK];
-        // Black Pawns (Rank 7 -> board index 1)
-        board[1] = vec![B_PAWN; 8];
-        board[6] = vec![ W_PAWN; 8];
-        board[7] = vec![W_ROOK, W_KNIGHT, W_BISHOP, W_QUEEN, W_KING, W_BISHOP, W_KNIGHT, W_ROOK];
+        use PieceKind::*;
+        let back_rank = [Rook, Knight, Bishop, Queen, King, Bishop, Knight, Rook];
+        let mut board = Board::empty();
+        for (y, &kind) in back_rank.iter().enumerate() {
+            let at = |x| Square::new(x, y).unwrap();
+            // Black pieces on rank 8 (x = 0), pawns on rank 7 (x = 1)
+            board.set_piece(at(0), Piece::new(Color::Black, kind));
+            board.set_piece(at(1), Piece::new(Color::Black, Pawn));
+            board.set_piece(at(6), Piece::new(Color::White, Pawn));
+            board.set_piece(at(7), Piece::new(Color::White, kind));
+        }
 
-        ChessGame {board, current_turn: WHITE, white_can_castle_kingside: true, white_can_castle_queenside: true, black_can_castle_kingside: true, black_can_castle_queenside: true, en_passant_target: None}
+        let mut game = ChessGame {board, current_turn: Color::White, white_can_castle_kingside: true, white_can_castle_queenside: true, black_can_castle_kingside: true, black_can_castle_queenside: true, castling_rook_files: [7, 0, 7, 0], chess960: false, en_passant_target: None, halfmove_clock: 0, fullmove_number: 1, position_history: Vec::new(), termination: None, recorded_result: None, pending_promotion: None, history: Vec::new(), redo_stack: Vec::new(), annotations: Vec::new()};
+        game.position_history.push(game.position_key());
+        game
     }
 
     pub fn get_board_json(&self) -> String {
-        match serde_json::to_string(&self.board) {
+        let rows: Vec<Vec<i32>> = (0..8)
+            .map(|x| (0..8).map(|y| self.get_piece(x, y)).collect())
+            .collect();
+        match serde_json::to_string(&rows) {
             Ok(json) => json,
             // Todo:Consider using wasm_bindgen::JsValue for errors???
             Err(e) => format!("{{\"error\": \"{}\"}}", e),
@@ -32,28 +39,52 @@ impl ChessGame {
 
     // Rreturn a flattened 1D array which JS can handle
     pub fn get_board(&self) -> Vec<i32> {
-        self.board.iter().flat_map(|row| row.iter().cloned()).collect()
+        Square::all().map(|sq| Piece::code_of(self.board.piece_at(sq))).collect()
     }
 
     pub fn get_board_width(&self) -> usize {
-        // Assumes a square board or at least one row if not empty
-        self.board.get(0).map_or(0, |row| row.len())
+        8
     }
 
     pub fn get_board_height(&self) -> usize {
-        self.board.len()
+        8
+    }
+
+    pub fn get_halfmove_clock(&self) -> u32 {
+        self.halfmove_clock
+    }
+
+    pub fn get_fullmove_number(&self) -> u32 {
+        self.fullmove_number
     }
 
+    /// The piece code at (x, y), 0 for an empty or off-board square.
     pub fn get_piece(&self, x: usize, y: usize) -> i32 {
-        // Check bounds using usize which cannot be negative
-        if x >= self.get_board_height() || y >= self.get_board_width() {
-            return 0;
-        }
-        self.board[x][y]
+        Square::new(x, y).map_or(0, |sq| Piece::code_of(self.piece_at(sq)))
     }
 
     // Internal helper to check if coords are on the board
     pub(crate) fn is_on_board(x: i32, y: i32) -> bool {
-        x >= 0 && x < 8 && y >= 0 && y < 8
+        (0..8).contains(&x) && (0..8).contains(&y)
+    }
+}
+
+impl ChessGame {
+    pub fn piece_at(&self, sq: Square) -> Option<Piece> {
+        self.board.piece_at(sq)
+    }
+
+    pub fn turn(&self) -> Color {
+        self.current_turn
+    }
+
+    pub fn en_passant_target(&self) -> Option<Square> {
+        self.en_passant_target
+    }
+}
+
+impl Default for ChessGame {
+    fn default() -> Self {
+        Self::new()
     }
 }
@@ -0,0 +1,61 @@
+#[cfg(feature = "wasm")]
+use wasm_bindgen::prelude::*;
+
+use crate::movegen::Move;
+use crate::ChessGame;
+
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+impl ChessGame {
+    /// Takes back the last move. A pending promotion counts as the last move and is simply dropped.
+    /// Returns false when there is nothing to take back.
+    pub fn undo(&mut self) -> bool {
+        if self.pending_promotion.take().is_some() {
+            return true;
+        }
+        let last = match self.history.last() {
+            Some(&undo) => undo,
+            None => return false,
+        };
+        self.retract(last);
+        self.redo_stack.push(last);
+        // Whatever ended the game happened after this position
+        self.termination = None;
+        self.recorded_result = None;
+        true
+    }
+
+    /// Replays the last move taken back with `undo`. Returns false when there is none.
+    pub fn redo(&mut self) -> bool {
+        if self.pending_promotion.is_some() {
+            return false;
+        }
+        match self.redo_stack.pop() {
+            Some(undo) => {
+                let mv = undo.as_move();
+                self.apply(mv.from, mv.to, mv.promotion);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Number of half-moves played since the start or the last loaded FEN.
+    pub fn ply_count(&self) -> usize {
+        self.history.len()
+    }
+
+    /// The moves played so far serialized as a JSON array, see `history`.
+    pub fn history_json(&self) -> String {
+        match serde_json::to_string(&self.history()) {
+            Ok(json) => json,
+            Err(e) => format!("{{\"error\": \"{}\"}}", e),
+        }
+    }
+}
+
+impl ChessGame {
+    /// The moves played since the start or the last loaded FEN, oldest first.
+    pub fn history(&self) -> Vec<Move> {
+        self.history.iter().map(|undo| undo.as_move()).collect()
+    }
+}
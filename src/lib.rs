@@ -1,22 +1,81 @@
 mod utils;
 mod constants;
+mod types;
+mod error;
+mod bitboard;
+mod board;
 
 mod game;
 mod validation;
 mod moves;
+mod movegen;
 mod fen;
+mod zobrist;
+mod draw;
+mod status;
+mod perft;
+mod history;
+mod san;
+mod uci;
+mod pgn;
+mod tree;
+mod annotation;
+mod search;
+mod epd;
+mod chess960;
+mod setup;
 
+#[cfg(feature = "wasm")]
 use wasm_bindgen::prelude::*;
 
-#[wasm_bindgen]
+use crate::board::Board;
+
+pub use crate::types::{Color, Piece, PieceKind, Square};
+pub use crate::error::{ChessError, PromotionError};
+pub use crate::moves::{CastleSide, MoveTarget, Undo};
+pub use crate::movegen::Move;
+pub use crate::validation::IllegalMoveReason;
+pub use crate::fen::{FenError, FenErrorKind, FenField};
+pub use crate::san::SanError;
+pub use crate::pgn::{PgnError, PgnErrorKind, PgnGame};
+pub use crate::tree::{GameTree, Node};
+pub use crate::annotation::{Annotation, Eval};
+pub use crate::search::{SearchResult, MATE};
+pub use crate::epd::{Epd, EpdError, SuiteResult};
+pub use crate::setup::PositionProblem;
+pub use crate::status::{DrawReason, GameStatus};
+
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
 #[derive(Debug, Clone)]
 pub struct ChessGame {
-    board: Vec<Vec<i32>>,
-    current_turn: i32,
+    board: Board,
+    current_turn: Color,
     white_can_castle_kingside: bool,
     white_can_castle_queenside: bool,
     black_can_castle_kingside: bool,
     black_can_castle_queenside: bool,
+    // Files of the rooks the castling rights belong to, in the same order
+    castling_rook_files: [usize; 4],
+    // Chess960 rules, where castling is written as the king taking its own rook
+    chess960: bool,
 
-    en_passant_target: Option<(usize,usize)>
-}
\ No newline at end of file
+    en_passant_target: Option<Square>,
+    // Plies since the last capture or pawn move, for the fifty-move rule
+    halfmove_clock: u32,
+    // Starts at 1 and increments after Black's move
+    fullmove_number: u32,
+    // Position keys after every move, used to detect repetitions
+    position_history: Vec<u64>,
+    // Set when the game ends off the board: resignation, timeout or a draw by agreement or claim
+    termination: Option<GameStatus>,
+    // The result a loaded PGN gave for a game the position doesn't end, such as a resignation
+    recorded_result: Option<&'static str>,
+    // A pawn move to the last rank waiting for `promote_pawn` to choose the piece
+    pending_promotion: Option<(Square, Square)>,
+    // Every move played since the start or the last loaded FEN
+    history: Vec<Undo>,
+    // Moves taken back with `undo`, most recent last
+    redo_stack: Vec<Undo>,
+    // Annotations read from PGN: the game's own first, then one per ply of the history
+    annotations: Vec<Annotation>,
+}
@@ -0,0 +1,167 @@
+use serde::Serialize;
+#[cfg(feature = "wasm")]
+use wasm_bindgen::prelude::*;
+
+use crate::bitboard::{self, Bitboard, bit, squares, EMPTY};
+use crate::chess960::{castled_squares, castling_index, home_rank};
+use crate::types::{Color, Piece, PieceKind, Square};
+use crate::ChessGame;
+
+/// A fully described move. In JSON squares are [x, y] board coordinates and pieces
+/// use the i32 encoding from `constants`; `captured` and `promotion` are null when absent.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct Move {
+    pub from: Square,
+    pub to: Square,
+    pub piece: Piece,
+    pub captured: Option<Piece>,
+    pub promotion: Option<PieceKind>,
+    pub is_castle: bool,
+    pub is_en_passant: bool,
+    pub is_double_push: bool,
+}
+
+impl Move {
+    fn quiet(from: usize, to: usize, piece: Piece) -> Move {
+        Move {
+            from: Square::from_index(from),
+            to: Square::from_index(to),
+            piece,
+            captured: None,
+            promotion: None,
+            is_castle: false,
+            is_en_passant: false,
+            is_double_push: false,
+        }
+    }
+}
+
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+impl ChessGame {
+    /// Every legal move for the side to move, serialized as a JSON array of moves.
+    pub fn legal_moves_json(&self) -> String {
+        match serde_json::to_string(&self.legal_moves()) {
+            Ok(json) => json,
+            Err(e) => format!("{{\"error\": \"{}\"}}", e),
+        }
+    }
+}
+
+impl ChessGame {
+    /// Generates every legal move for the side to move.
+    pub fn legal_moves(&self) -> Vec<Move> {
+        let mut moves = self.pseudo_legal_moves();
+        moves.retain(|mv| !self.leaves_king_in_check(mv.from, mv.to));
+        moves
+    }
+
+    /// Moves that follow the movement rules but may leave the own king in check.
+    /// Castling is the exception: it is only generated when fully legal.
+    fn pseudo_legal_moves(&self) -> Vec<Move> {
+        let color = self.current_turn;
+        let own = self.board.by_color(color);
+        let enemy = self.board.by_color(color.opponent());
+        let occupied = own | enemy;
+        let mut moves = Vec::with_capacity(64);
+
+        self.push_pawn_moves(&mut moves, color, enemy, occupied);
+
+        for &kind in &PieceKind::ALL[1..] {
+            let piece = Piece::new(color, kind);
+            for from in squares(self.board.pieces(kind, color)) {
+                let targets = match kind {
+                    PieceKind::Rook => bitboard::rook_attacks(from, occupied),
+                    PieceKind::Knight => bitboard::KNIGHT_ATTACKS[from],
+                    PieceKind::Bishop => bitboard::bishop_attacks(from, occupied),
+                    PieceKind::Queen => bitboard::queen_attacks(from, occupied),
+                    _ => bitboard::KING_ATTACKS[from],
+                };
+                for to in squares(targets & !own) {
+                    let mut mv = Move::quiet(from, to, piece);
+                    mv.captured = self.board.piece_at(Square::from_index(to));
+                    moves.push(mv);
+                }
+            }
+        }
+
+        self.push_castling_moves(&mut moves, color);
+        moves
+    }
+
+    fn push_pawn_moves(&self, moves: &mut Vec<Move>, color: Color, enemy: Bitboard, occupied: Bitboard) {
+        let is_white = color == Color::White;
+        let piece = Piece::new(color, PieceKind::Pawn);
+        let (start_rank, promotion_rank) = if is_white { (6, 0) } else { (1, 7) };
+        let ep_target = self.en_passant_target.map_or(EMPTY, |sq| bit(sq.index()));
+
+        for from in squares(self.board.pieces(PieceKind::Pawn, color)) {
+            let x = from / 8;
+            if x == promotion_rank {
+                continue; // Only reachable from a malformed position
+            }
+            let forward = if is_white { from - 8 } else { from + 8 };
+
+            let mut targets = Vec::with_capacity(4);
+            if occupied & bit(forward) == EMPTY {
+                targets.push(Move::quiet(from, forward, piece));
+                if x == start_rank {
+                    let double = if is_white { forward - 8 } else { forward + 8 };
+                    if occupied & bit(double) == EMPTY {
+                        let mut mv = Move::quiet(from, double, piece);
+                        mv.is_double_push = true;
+                        targets.push(mv);
+                    }
+                }
+            }
+            let attacks = bitboard::PAWN_ATTACKS[color.index()][from];
+            for to in squares(attacks & enemy) {
+                let mut mv = Move::quiet(from, to, piece);
+                mv.captured = self.board.piece_at(Square::from_index(to));
+                targets.push(mv);
+            }
+            for to in squares(attacks & ep_target & !occupied) {
+                let mut mv = Move::quiet(from, to, piece);
+                mv.captured = Square::new(x, to % 8).and_then(|sq| self.board.piece_at(sq));
+                mv.is_en_passant = true;
+                targets.push(mv);
+            }
+
+            for mv in targets {
+                if mv.to.x() == promotion_rank {
+                    // Queen first, so callers that pick the first match get the usual choice
+                    for &promotion in &PieceKind::PROMOTIONS {
+                        let mut promo = mv;
+                        promo.promotion = Some(promotion);
+                        moves.push(promo);
+                    }
+                } else {
+                    moves.push(mv);
+                }
+            }
+        }
+    }
+
+    fn push_castling_moves(&self, moves: &mut Vec<Move>, color: Color) {
+        let home_rank = home_rank(color);
+        let king = match self.board.king_square(color) {
+            Some(king) if king.x() == home_rank => king,
+            _ => return,
+        };
+
+        for kingside in [true, false] {
+            let index = castling_index(color, kingside);
+            // A Chess960 king castles by moving onto its rook
+            let target = if self.chess960 {
+                Square::new(home_rank, self.castling_rook_files[index]).unwrap()
+            } else {
+                castled_squares(home_rank, kingside).0
+            };
+            // Also checks the castling rights
+            if self.castling_right(index) && self.check_king_move(king, target, color).is_ok() {
+                let mut mv = Move::quiet(king.index(), target.index(), Piece::new(color, PieceKind::King));
+                mv.is_castle = true;
+                moves.push(mv);
+            }
+        }
+    }
+}
@@ -1,211 +1,187 @@
+use serde::Serialize;
+#[cfg(feature = "wasm")]
 use wasm_bindgen::prelude::*;
-use wasm_bindgen::JsValue;
 
-use crate::constants::{
-    W_KING, B_KING,
-    WHITE, BLACK,
-};
-use crate::utils;
+use crate::chess960::{castled_squares, castling_index};
+use crate::error::{ChessError, PromotionError};
+use crate::movegen::Move;
+use crate::types::{Color, Piece, PieceKind, Square};
 use crate::ChessGame;
 
-#[wasm_bindgen]
-impl ChessGame {
-    #[wasm_bindgen]
-    pub fn make_move(&mut self, start_x: usize, start_y: usize, end_x: usize, end_y: usize) -> Result<Option<Vec<usize>>, JsValue> {
-
-        let (sx, sy, ex, ey) = (start_x as i32, start_y as i32, end_x as i32, end_y as i32);
-        let piece_at_start = self.get_piece(start_x, start_y);
-        // let piece_type_at_start = utils::get_piece_type(piece_at_start);
-        let piece_color_at_start = utils::get_piece_color(piece_at_start);
-
-        if !self.is_valid_move(sx, sy, ex, ey) {
-            let error_msg = if piece_at_start == 0 {
-                "No piece at starting square.".to_string()
-            } else if piece_color_at_start != self.current_turn {
-                format!("Not {}'s turn.", if self.current_turn == WHITE { "White" } else { "Black" })
-            } else {
-                format!("Invalid move for piece from ({},{}) to ({},{}). Check move shape, path, or if king would be in check.", sx, sy, ex, ey)
-            };
-            return Err(JsValue::from_str(&error_msg));
-        }
-
-        // Perform the move internally. This *doesn't* promote the pawn or switch turns.
-        self.make_move_internal(sx, sy, ex, ey, false)
-            .map_err(|e| JsValue::from_str(&format!("Internal move error: {}", e)))?;
-
-        // After `make_move_internal` completes, the piece is at `end_x, end_y`.
-        // Check for promotion after the move has been applied to the board.
-        let promotion_coords: Option<Vec<usize>> = {
-            let moved_piece = self.get_piece(end_x, end_y);
-            let moved_piece_type = utils::get_piece_type(moved_piece);
-            let moved_piece_color = utils::get_piece_color(moved_piece);
+/// Everything `unmake` needs to take back a move applied with `make`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Undo {
+    from: Square,
+    to: Square,
+    promotion: Option<PieceKind>,
+    moved: Piece,
+    captured: Option<Piece>,
+    // Differs from `to` only for en passant
+    captured_square: Square,
+    // Rook start and end squares when the move was a castle
+    rook_move: Option<(Square, Square)>,
+    // White kingside, white queenside, black kingside, black queenside
+    castling: [bool; 4],
+    en_passant_target: Option<Square>,
+    halfmove_clock: u32,
+    fullmove_number: u32,
+}
 
-            let is_pawn_promotion_rank = (moved_piece_color == WHITE && end_x == 0) ||
-                                         (moved_piece_color == BLACK && end_x == 7);
+impl Undo {
+    /// The piece that was captured, if any.
+    pub fn captured(&self) -> Option<Piece> {
+        self.captured
+    }
 
-            if moved_piece_type == 1 && is_pawn_promotion_rank {
-                Some(vec![end_x, end_y])
-            } else {
-                None
-            }
-        };
+    pub fn moved(&self) -> Piece {
+        self.moved
+    }
 
-        // If a promotion is pending, the turn is NOT switched yet.
-        // It will be switched after the promotion choice is made and applied by `promote_pawn`.
-        if promotion_coords.is_none() {
-            // If no promotion, then switch turns as usual
-            self.current_turn = if self.current_turn == WHITE { BLACK } else { WHITE };
+    /// The move this record takes back.
+    pub fn as_move(&self) -> Move {
+        Move {
+            from: self.from,
+            to: self.to,
+            piece: self.moved,
+            captured: self.captured,
+            promotion: self.promotion,
+            is_castle: self.rook_move.is_some(),
+            is_en_passant: self.captured_square != self.to,
+            is_double_push: self.moved.kind == PieceKind::Pawn && self.from.x().abs_diff(self.to.x()) == 2,
         }
-
-        // Return the promotion coordinates (if any) to JavaScript
-        Ok(promotion_coords)
     }
+}
 
-    // Internal helper to perform the move mechanics and state updates.
-    // `is_simulation` prevents recursive state updates during validation check.
-    pub(crate) fn make_move_internal(&mut self, start_x: i32, start_y: i32, end_x: i32, end_y: i32, is_simulation: bool) -> Result<(), String> {
-        let piece = self.board[start_x as usize][start_y as usize];
-        let piece_type = utils::get_piece_type(piece);
-        let piece_color = utils::get_piece_color(piece); // Keep this for castling rights updates
-
-        // Handle En Passant Capture (remove the passed pawn)
-        if piece_type == 1 && self.is_en_passant_move(start_x, start_y, end_x, end_y) {
-            let captured_pawn_x = start_x; // Captured pawn is on the start rank
-            let captured_pawn_y = end_y;   // Captured pawn is on the destination file
-            self.board[captured_pawn_x as usize][captured_pawn_y as usize] = 0;
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CastleSide {
+    Kingside,
+    Queenside,
+}
+
+/// A legal destination for one piece, with what the UI needs to highlight it and pick a sound.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct MoveTarget {
+    pub to: Square,
+    pub capture: bool,
+    pub captured: Option<Piece>,
+    pub en_passant: bool,
+    pub castle: Option<CastleSide>,
+    pub promotion: bool,
+    /// For promotions, check and checkmate assume a queen.
+    pub check: bool,
+    pub checkmate: bool,
+}
+
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+impl ChessGame {
+    /// Plays a move. A pawn reaching the last rank promotes to `promotion` in the same step.
+    /// Without a promotion piece such a move is held back as a pending promotion and its
+    /// target square is returned; `promote_pawn` then completes it.
+    #[cfg_attr(feature = "wasm", wasm_bindgen)]
+    pub fn make_move(&mut self, start_x: usize, start_y: usize, end_x: usize, end_y: usize, promotion: Option<i32>) -> Result<Option<Vec<usize>>, ChessError> {
+
+        if self.termination.is_some() {
+            return Err(ChessError::GameOver);
+        }
+        if self.pending_promotion.is_some() {
+            return Err(ChessError::PromotionPending);
         }
 
-        // Move the piece
-        self.board[end_x as usize][end_y as usize] = piece;
-        self.board[start_x as usize][start_y as usize] = 0;
-
-        // Handle Castling (move the rook)
-        if piece_type == 6 && (end_y - start_y).abs() == 2 {
-            let (rook_start_y, rook_end_y) = if end_y > start_y {
-                (7, 5) // Kingside: H-file rook to F-file
-            } else {
-                (0, 3) // Queenside: A-file rook to D-file
-            };
-            let rook = self.board[start_x as usize][rook_start_y];
-            self.board[start_x as usize][rook_end_y] = rook;
-            self.board[start_x as usize][rook_start_y] = 0;
+        let from = Square::new(start_x, start_y).ok_or(ChessError::OffBoard { x: start_x, y: start_y })?;
+        let to = Square::new(end_x, end_y).ok_or(ChessError::OffBoard { x: end_x, y: end_y })?;
+        let piece = self.piece_at(from).ok_or(ChessError::EmptySquare(from))?;
+        if piece.color != self.current_turn {
+            return Err(ChessError::WrongTurn(self.current_turn));
         }
+        self.explain_move(from, to)
+            .map_err(|reason| ChessError::IllegalMove { from, to, reason })?;
 
-        // Post-Move State Update (only if not a simulation)
-        if !is_simulation {
-            // Update Castling Rights
-            // If King moved
-            if piece_type == 6 {
-                if piece_color == WHITE {
-                    self.white_can_castle_kingside = false;
-                    self.white_can_castle_queenside = false;
-                } else {
-                    self.black_can_castle_kingside = false;
-                    self.black_can_castle_queenside = false;
-                }
+        let promotion_rank = if piece.color == Color::White { 0 } else { 7 };
+        let is_promotion = piece.kind == PieceKind::Pawn && end_x == promotion_rank;
+
+        match promotion {
+            Some(new_piece) if is_promotion => {
+                let kind = Self::check_promotion_piece(new_piece, piece.color)?;
+                self.make(from, to, Some(kind));
+                Ok(None)
             }
-            // If Rook moved (from its starting square)
-            if piece_type == 2 {
-                if piece_color == WHITE {
-                    if start_x == 7 && start_y == 0 { self.white_can_castle_queenside = false; }
-                    if start_x == 7 && start_y == 7 { self.white_can_castle_kingside = false; }
-                } else { // Black
-                    if start_x == 0 && start_y == 0 { self.black_can_castle_queenside = false; }
-                    if start_x == 0 && start_y == 7 { self.black_can_castle_kingside = false; }
-                }
+            Some(_) => Err(PromotionError::NotAPromotion.into()),
+            None if is_promotion => {
+                // Nothing is applied until the piece is chosen, so the position stays consistent
+                self.pending_promotion = Some((from, to));
+                Ok(Some(vec![end_x, end_y]))
+            }
+            None => {
+                self.make(from, to, None);
+                Ok(None)
             }
-
-            // Set potential En Passant target for the *next* turn
-             if piece_type == 1 && (end_x - start_x).abs() == 2 {
-                 let target_rank = (start_x + end_x) / 2; // Rank behind the pawn
-                 self.en_passant_target = Some((target_rank as usize, start_y as usize));
-             } else {
-                 self.en_passant_target = None; // Clear en passant target if not a double pawn push???
-             }
-
         }
-
-        Ok(())
     }
 
+    /// Destinations of the legal moves from (x, y), flattened as [x1, y1, x2, y2, ...].
     pub fn get_moves(&self, x:usize, y:usize) -> Vec<usize> {
-        let mut moves= Vec::new();
-
-        if x > 7 || y > 7 {
-            return moves;
-        }
+        let from = match Square::new(x, y) {
+            Some(sq) => sq,
+            None => return Vec::new(),
+        };
+        let mut targets: Vec<Square> = self.legal_moves()
+            .into_iter()
+            .filter(|mv| mv.from == from)
+            .map(|mv| mv.to)
+            .collect();
+        // Promotions produce one move per piece for the same destination
+        targets.sort_unstable();
+        targets.dedup();
+        targets.into_iter().flat_map(|sq| vec![sq.x(), sq.y()]).collect()
+    }
 
-        let piece= self.get_piece(x, y);
-        if piece == 0 {
-            return moves;
-        }
-        if utils::get_piece_color(piece) != self.current_turn {
-            return moves;
+    /// The legal destinations from (x, y) with their flags, serialized as a JSON array, see `move_targets`.
+    pub fn get_moves_json(&self, x: usize, y: usize) -> String {
+        let targets = Square::new(x, y).map_or_else(Vec::new, |from| self.move_targets(from));
+        match serde_json::to_string(&targets) {
+            Ok(json) => json,
+            Err(e) => format!("{{\"error\": \"{}\"}}", e),
         }
-
-
-        for i in 0..8{
-            for j in 0..8{
-                if self.is_valid_move(x as i32, y as i32, i as i32, j as i32) {
-                    moves.push(i);
-                    moves.push(j);
-                }
-            }
-        }
-        moves
     }
 
     
-    #[wasm_bindgen]
+    #[cfg_attr(feature = "wasm", wasm_bindgen)]
     pub fn get_king_position(&self) -> Vec<i32> {
-        let king_piece = if self.current_turn == WHITE { W_KING } else { B_KING };
-
-        for r in 0..8 {
-            for c in 0..8 {
-                if self.board[r][c] == king_piece {
-                    return vec![r as i32, c as i32];
-                }
-            }
+        match self.board.king_square(self.current_turn) {
+            Some(sq) => vec![sq.x() as i32, sq.y() as i32],
+            // Should not happen in a valid game, but return an empty vector if king not found
+            None => Vec::new(),
         }
-        // Should not happen in a valid game, but return an empty vector if king not found
-        Vec::new() 
     }
 
     // Checkmate logic needs to consider the current player's turn.
     // If checkmate is true, it means the *current player* has no legal moves.
     pub fn checkmate(&self) -> bool {
         // If the current player is not in check, it cannot be checkmate.
-        if !self.is_check(self.current_turn) {
+        if !self.in_check(self.current_turn) {
             return false;
         }
-        // If in check, check if any piece of the current turn can make a valid move.
-        // If no moves are found, it's checkmate.
-        for r in 0..8 {
-            for c in 0..8 {
-                let temp_piece = self.get_piece(r, c);
-                if temp_piece != 0 && utils::get_piece_color(temp_piece) == self.current_turn {
-                    // If any piece can make a valid move, it's not checkmate
-                    if self.get_moves(r, c).len() > 0 {
-                        return false;
-                    }
-                }
-            }
-        }
-        true // No legal moves found for the player in check -> checkmate
+        // If in check and there is no legal move, it's checkmate.
+        self.legal_moves().is_empty()
     }
 
     pub fn get_current_turn(&self) -> i32 {
-        self.current_turn
+        self.current_turn.code()
     }
-    pub fn change_turn(&mut self) {
-        self.current_turn = if self.current_turn == WHITE { BLACK } else { WHITE };
+    pub fn change_turn(&mut self) -> Result<(), ChessError> {
+        if self.pending_promotion.is_some() {
+            return Err(ChessError::PromotionPending);
+        }
+        self.switch_turn();
+        Ok(())
     }
     pub fn check(&self) -> bool {
-        self.is_check(self.current_turn)
+        self.in_check(self.current_turn)
     }
 
     pub fn can_castle_kingside(&self) -> bool {
-        if self.current_turn == WHITE {
+        if self.current_turn == Color::White {
             self.white_can_castle_kingside
         }
         else {
@@ -215,59 +191,232 @@ impl ChessGame {
 
     pub fn is_stalemate(&self) -> bool {
         // If the current player is in check, it cannot be stalemate.
-        if self.is_check(self.current_turn) {
+        if self.in_check(self.current_turn) {
             return false;
         }
-        // If not in check, check if any piece of the current turn can make a valid move.
-        // If no moves are found, it's stalemate.
-        for r in 0..8 {
-            for c in 0..8 {
-                let piece = self.get_piece(r, c);
-                if piece != 0 && utils::get_piece_color(piece) == self.current_turn {
-                    // If any piece can make a valid move, it's not stalemate
-                    if self.get_moves(r, c).len() > 0 {
-                        return false;
-                    }
-                }
+        // Not in check, but no legal moves -> stalemate
+        self.legal_moves().is_empty()
+    }
+
+    /// Whether the game has ended, on the board (checkmate, stalemate, a dead position,
+    /// fivefold repetition, the seventy-five-move rule) or by resignation, timeout or a draw.
+    pub fn is_game_over(&self) -> bool {
+        self.status().is_over()
+    }
+
+    /// Completes the pending promotion on (x, y) with `new_piece` and passes the turn.
+    #[cfg_attr(feature = "wasm", wasm_bindgen)]
+    pub fn promote_pawn(&mut self, x: usize, y: usize, new_piece: i32) -> Result<(), ChessError> {
+        let (from, to) = self.pending_promotion.ok_or(PromotionError::NonePending)?;
+        if Square::new(x, y) != Some(to) {
+            return Err(PromotionError::WrongSquare.into());
+        }
+
+        let pawn_color = self.current_turn;
+        let kind = Self::check_promotion_piece(new_piece, pawn_color)?;
+
+        self.pending_promotion = None;
+        self.make(from, to, Some(kind));
+        Ok(())
+    }
+
+    pub fn has_pending_promotion(&self) -> bool {
+        self.pending_promotion.is_some()
+    }
+
+    /// Drops the pending promotion, leaving the pawn where it was.
+    pub fn cancel_promotion(&mut self) {
+        self.pending_promotion = None;
+    }
+}
+
+impl ChessGame {
+    /// One entry per legal destination of the piece on `from`, promotions included once.
+    pub fn move_targets(&self, from: Square) -> Vec<MoveTarget> {
+        let mut targets: Vec<MoveTarget> = Vec::new();
+        // Each move is tried on one copy and taken back again
+        let mut scratch = self.clone();
+        for mv in self.legal_moves().into_iter().filter(|mv| mv.from == from) {
+            // Promotions come queen first, so the queen decides check and checkmate
+            if targets.iter().any(|target| target.to == mv.to) {
+                continue;
             }
+            let (check, checkmate) = scratch.check_after(&mv);
+            targets.push(MoveTarget {
+                to: mv.to,
+                capture: mv.captured.is_some(),
+                captured: mv.captured,
+                en_passant: mv.is_en_passant,
+                castle: match mv.is_castle {
+                    true if mv.to.y() > mv.from.y() => Some(CastleSide::Kingside),
+                    true => Some(CastleSide::Queenside),
+                    false => None,
+                },
+                promotion: mv.promotion.is_some(),
+                check,
+                checkmate,
+            });
         }
-        true // Not in check, but no legal moves -> stalemate
+        targets
     }
 
-    #[wasm_bindgen]
-    pub fn promote_pawn(&mut self, x: usize, y: usize, new_piece: i32) -> Result<(), JsValue> {
-        if x > 7 || y > 7 {
-            return Err(JsValue::from_str("Coordinates out of bounds"));
+    // Ensure the new piece is a Rook, Knight, Bishop or Queen of the promoting side's color
+    fn check_promotion_piece(new_piece: i32, pawn_color: Color) -> Result<PieceKind, PromotionError> {
+        match Piece::from_code(new_piece) {
+            Some(piece) if piece.color == pawn_color && PieceKind::PROMOTIONS.contains(&piece.kind) => Ok(piece.kind),
+            _ => Err(PromotionError::InvalidPiece),
         }
-        let piece = self.get_piece(x, y);
-        let piece_type = utils::get_piece_type(piece);
-        let piece_color = utils::get_piece_color(piece);
+    }
 
-        if piece == 0 || piece_type != 1 {
-            return Err(JsValue::from_str("No pawn at specified coordinates for promotion"));
+    pub(crate) fn switch_turn(&mut self) {
+        self.current_turn = self.current_turn.opponent();
+    }
+}
+
+impl ChessGame {
+    /// Applies a move without validating it and switches the turn.
+    /// `promotion` is the piece a pawn reaching the last rank turns into; `None` leaves it a pawn.
+    /// The returned record restores the previous position when passed to `unmake`.
+    ///
+    /// A new move starts a new line, so moves taken back with `undo` can't be redone afterwards.
+    ///
+    /// Panics if `from` is empty.
+    pub fn make(&mut self, from: Square, to: Square, promotion: Option<PieceKind>) -> Undo {
+        self.redo_stack.clear();
+        self.recorded_result = None;
+        // Annotations on the moves taken back don't belong to this one
+        self.annotations.truncate(self.history.len() + 1);
+        self.apply(from, to, promotion)
+    }
+
+    /// Takes back the move that produced `undo`. Moves must be unmade in reverse order.
+    /// Like `make`, this leaves nothing to redo.
+    pub fn unmake(&mut self, undo: Undo) {
+        self.redo_stack.clear();
+        self.recorded_result = None;
+        self.retract(undo);
+    }
+
+    // `make` and `unmake` without touching the redo stack, for undo, redo and perft
+    pub(crate) fn apply(&mut self, from: Square, to: Square, promotion: Option<PieceKind>) -> Undo {
+        let piece = self.board.piece_at(from).expect("make: no piece on the start square");
+        let is_pawn = piece.kind == PieceKind::Pawn;
+
+        let mut undo = Undo {
+            from,
+            to,
+            promotion: None,
+            moved: piece,
+            captured: None,
+            captured_square: to,
+            rook_move: None,
+            castling: [
+                self.white_can_castle_kingside,
+                self.white_can_castle_queenside,
+                self.black_can_castle_kingside,
+                self.black_can_castle_queenside,
+            ],
+            en_passant_target: self.en_passant_target,
+            halfmove_clock: self.halfmove_clock,
+            fullmove_number: self.fullmove_number,
+        };
+
+        // Castling moves the rook too; a Chess960 king may land where either piece stood
+        let castling = if piece.kind == PieceKind::King { self.castling_rook(from, to, piece.color) } else { None };
+        if let Some((kingside, rook_start)) = castling {
+            let (king_end, rook_end) = castled_squares(from.x(), kingside);
+            let rook = self.board.piece_at(rook_start);
+            self.board.remove_piece(from);
+            self.board.remove_piece(rook_start);
+            self.board.set_piece(king_end, piece);
+            if let Some(rook) = rook {
+                self.board.set_piece(rook_end, rook);
+            }
+            undo.rook_move = Some((rook_start, rook_end));
+        } else {
+            // En passant removes the pawn beside the start square, not the one on the target
+            if is_pawn && from.y() != to.y() && self.en_passant_target == Some(to) {
+                undo.captured_square = Square::new(from.x(), to.y()).unwrap();
+            }
+            undo.captured = self.board.piece_at(undo.captured_square);
+            self.board.remove_piece(undo.captured_square);
+
+            self.board.remove_piece(from);
+            match promotion {
+                Some(kind) if is_pawn && (to.x() == 0 || to.x() == 7) => {
+                    self.board.set_piece(to, Piece::new(piece.color, kind));
+                    undo.promotion = Some(kind);
+                }
+                _ => self.board.set_piece(to, piece),
+            }
         }
-        
-        // Ensure the pawn is on the correct promotion rank
-        let promotion_rank = if piece_color == WHITE { 0 } else { 7 };
-        if x != promotion_rank {
-            return Err(JsValue::from_str("Pawn is not on the promotion rank"));
+
+        // Moving the king loses both its castling rights, and any move from or onto a castling
+        // rook's square loses that one, which covers capturing the rook at home.
+        if piece.kind == PieceKind::King {
+            self.set_castling_right(castling_index(piece.color, true), false);
+            self.set_castling_right(castling_index(piece.color, false), false);
+        }
+        for index in 0..4 {
+            let rook_home = Square::new(if index < 2 { 7 } else { 0 }, self.castling_rook_files[index]).unwrap();
+            if from == rook_home || to == rook_home {
+                self.set_castling_right(index, false);
+            }
         }
 
-        // Basic validation for new_piece (ensure it's a valid promotion piece of the correct color)
-        let new_piece_type = utils::get_piece_type(new_piece);
-        let new_piece_color = utils::get_piece_color(new_piece);
-        
-        let is_valid_promotion_piece = match new_piece_type {
-            2 | 3 | 4 | 5 => true, // Rook, Knight, Bishop, Queen
-            _ => false,
+        // Set potential En Passant target for the *next* turn
+        self.en_passant_target = if is_pawn && (to.x() as i32 - from.x() as i32).abs() == 2 {
+            Square::new((from.x() + to.x()) / 2, from.y())
+        } else {
+            None
         };
 
-        if !is_valid_promotion_piece || new_piece_color != piece_color {
-            return Err(JsValue::from_str("Invalid piece for promotion"));
+        if is_pawn || undo.captured.is_some() {
+            self.halfmove_clock = 0;
+        } else {
+            self.halfmove_clock += 1;
+        }
+        if self.current_turn == Color::Black {
+            self.fullmove_number += 1;
         }
 
-        // Replace the pawn with the new piece
-        self.board[x][y] = new_piece;
-        Ok(())
+        self.switch_turn();
+        self.position_history.push(self.position_key());
+        self.history.push(undo);
+        undo
+    }
+
+    pub(crate) fn retract(&mut self, undo: Undo) {
+        self.position_history.pop();
+        self.history.pop();
+        // Not a switch: `change_turn` may have handed the move over since
+        self.current_turn = undo.moved.color;
+
+        if let Some((rook_start, rook_end)) = undo.rook_move {
+            // Both pieces come off first, since a Chess960 king may return to the rook's square
+            let (king_end, _) = castled_squares(rook_end.x(), rook_end.y() == 5);
+            let rook = self.board.piece_at(rook_end);
+            self.board.remove_piece(king_end);
+            self.board.remove_piece(rook_end);
+            self.board.set_piece(undo.from, undo.moved);
+            if let Some(rook) = rook {
+                self.board.set_piece(rook_start, rook);
+            }
+        } else {
+            self.board.remove_piece(undo.to);
+            self.board.set_piece(undo.from, undo.moved);
+            if let Some(captured) = undo.captured {
+                self.board.set_piece(undo.captured_square, captured);
+            }
+        }
+
+        let [white_kingside, white_queenside, black_kingside, black_queenside] = undo.castling;
+        self.white_can_castle_kingside = white_kingside;
+        self.white_can_castle_queenside = white_queenside;
+        self.black_can_castle_kingside = black_kingside;
+        self.black_can_castle_queenside = black_queenside;
+        self.en_passant_target = undo.en_passant_target;
+        self.halfmove_clock = undo.halfmove_clock;
+        self.fullmove_number = undo.fullmove_number;
     }
 }
@@ -0,0 +1,46 @@
+#[cfg(feature = "wasm")]
+use wasm_bindgen::prelude::*;
+
+use crate::movegen::Move;
+use crate::ChessGame;
+
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+impl ChessGame {
+    /// Counts the leaf nodes of the legal move tree `depth` plies deep.
+    /// The game is left exactly as it was found.
+    pub fn perft(&mut self, depth: u32) -> u64 {
+        if depth == 0 {
+            return 1;
+        }
+        let moves = self.legal_moves();
+        // Bulk counting: the last ply only needs the number of moves
+        if depth == 1 {
+            return moves.len() as u64;
+        }
+        let mut nodes = 0;
+        for mv in moves {
+            let undo = self.apply(mv.from, mv.to, mv.promotion);
+            nodes += self.perft(depth - 1);
+            self.retract(undo);
+        }
+        nodes
+    }
+}
+
+impl ChessGame {
+    /// Perft split by root move, for tracking down which subtree disagrees with a reference count.
+    pub fn perft_divide(&mut self, depth: u32) -> Vec<(Move, u64)> {
+        if depth == 0 {
+            return Vec::new();
+        }
+        self.legal_moves()
+            .into_iter()
+            .map(|mv| {
+                let undo = self.apply(mv.from, mv.to, mv.promotion);
+                let nodes = self.perft(depth - 1);
+                self.retract(undo);
+                (mv, nodes)
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,529 @@
+use std::fmt;
+
+#[cfg(feature = "wasm")]
+use wasm_bindgen::prelude::*;
+
+use crate::annotation::{nag_for_glyph, Annotation};
+use crate::error::ChessError;
+use crate::fen::FenError;
+use crate::movegen::Move;
+use crate::san::SanError;
+use crate::status::GameStatus;
+use crate::tree::GameTree;
+use crate::types::Color;
+use crate::ChessGame;
+
+/// The Seven Tag Roster with the values used when a tag isn't given.
+const SEVEN_TAG_ROSTER: [(&str, &str); 7] = [
+    ("Event", "?"),
+    ("Site", "?"),
+    ("Date", "????.??.??"),
+    ("Round", "?"),
+    ("White", "?"),
+    ("Black", "?"),
+    ("Result", "*"),
+];
+
+// Export format keeps movetext lines under 80 characters
+const LINE_WIDTH: usize = 79;
+
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+impl ChessGame {
+    /// The game as PGN, see `pgn`. `tags_json` is a JSON object of tag names to
+    /// values, e.g. `{"White": "alice", "Black": "bob", "TimeControl": "300"}`.
+    pub fn to_pgn(&self, tags_json: &str) -> Result<String, ChessError> {
+        let tags = parse_tags_json(tags_json)?;
+        let pairs: Vec<(&str, &str)> = tags.iter().map(|(name, value)| (name.as_str(), value.as_str())).collect();
+        Ok(self.pgn(&pairs))
+    }
+
+    /// Replaces the game with the first game of a PGN text, moves and all.
+    pub fn load_pgn(&mut self, pgn: &str) -> Result<(), ChessError> {
+        *self = ChessGame::from_pgn(pgn)?;
+        Ok(())
+    }
+}
+
+impl ChessGame {
+    /// The moves played since the start or the last loaded FEN as a PGN game.
+    ///
+    /// `tags` fill in the Seven Tag Roster, which defaults to "?" values, and any others
+    /// follow it in the order given. The Result tag and token come from `status`, or while
+    /// the position doesn't end the game, from the PGN the game was loaded from.
+    /// A game that didn't start from the standard position gets SetUp and FEN tags, and a
+    /// Chess960 game a Variant tag. Annotations from a loaded PGN are written back on the
+    /// moves that are still played.
+    pub fn pgn(&self, tags: &[(&str, &str)]) -> String {
+        let mut start = self.clone();
+        start.pending_promotion = None;
+        while let Some(&undo) = start.history.last() {
+            start.unmake(undo);
+        }
+        let result = self.pgn_result();
+
+        let mut tokens: Vec<String> = self.annotations.first().and_then(Annotation::pgn_comment).into_iter().collect();
+        let mut position = start.clone();
+        let mut numbered = true;
+        let none = Annotation::default();
+        for (ply, mv) in self.history().iter().enumerate() {
+            let annotation = self.annotations.get(ply + 1).unwrap_or(&none);
+            numbered = annotated_move_tokens(&mut position, mv, numbered, annotation, &mut tokens);
+            position.make(mv.from, mv.to, mv.promotion);
+        }
+        write_pgn(&start, result, tags, tokens)
+    }
+
+    /// The annotation a loaded PGN had on the move at `ply`, counting from 1, or on the
+    /// game as a whole at 0. `None` when there is none, as on a move played since.
+    pub fn annotation(&self, ply: usize) -> Option<&Annotation> {
+        if ply > self.history.len() {
+            return None;
+        }
+        self.annotations.get(ply)
+    }
+
+    /// The result to write: the position's own, else the one recorded by a loaded PGN.
+    pub(crate) fn pgn_result(&self) -> &'static str {
+        match self.status() {
+            GameStatus::Ongoing | GameStatus::Check => self.recorded_result.unwrap_or("*"),
+            status => status.outcome(),
+        }
+    }
+}
+
+/// Tag pairs from a JSON object of tag names to string values.
+pub(crate) fn parse_tags_json(tags_json: &str) -> Result<Vec<(String, String)>, ChessError> {
+    let tags: serde_json::Map<String, serde_json::Value> =
+        serde_json::from_str(tags_json).map_err(|e| ChessError::InvalidTags(e.to_string()))?;
+    tags.into_iter()
+        .map(|(name, value)| match value {
+            serde_json::Value::String(value) => Ok((name, value)),
+            _ => Err(ChessError::InvalidTags(format!("{} is not a string", name))),
+        })
+        .collect()
+}
+
+/// The move number, when one is due, and the SAN of `mv` played in `position`.
+/// Black's moves are numbered only where a line starts or resumes.
+fn move_tokens(position: &mut ChessGame, mv: &Move, numbered: bool) -> String {
+    let san = position.san_in_place(mv);
+    if position.current_turn == Color::White {
+        format!("{}. {}", position.fullmove_number, san)
+    } else if numbered {
+        format!("{}... {}", position.fullmove_number, san)
+    } else {
+        san
+    }
+}
+
+/// Writes `mv`, played in `position`, with its NAGs and comment. Returns whether there
+/// was a comment, after which the next move needs its number again.
+pub(crate) fn annotated_move_tokens(position: &mut ChessGame, mv: &Move, numbered: bool, annotation: &Annotation, movetext: &mut Vec<String>) -> bool {
+    movetext.push(move_tokens(position, mv, numbered));
+    movetext.extend(annotation.nags.iter().map(|nag| format!("${}", nag)));
+    match annotation.pgn_comment() {
+        Some(comment) => {
+            movetext.push(comment);
+            true
+        }
+        None => false,
+    }
+}
+
+/// A PGN game starting from `start`: the tag pairs, then `movetext` wrapped into lines
+/// and ended by `result`.
+pub(crate) fn write_pgn(start: &ChessGame, result: &str, tags: &[(&str, &str)], movetext: Vec<String>) -> String {
+    let mut header = Vec::new();
+    for &(name, default) in &SEVEN_TAG_ROSTER {
+        let value = match name {
+            "Result" => result,
+            _ => tags.iter().find(|&&(tag, _)| tag == name).map_or(default, |&(_, value)| value),
+        };
+        header.push((name, value.to_string()));
+    }
+    if start.chess960 {
+        header.push(("Variant", "Chess960".to_string()));
+    }
+    let start_fen = start.fen().expect("no promotion is pending");
+    if start_fen != ChessGame::new().fen().expect("no promotion is pending") {
+        header.push(("SetUp", "1".to_string()));
+        header.push(("FEN", start_fen));
+    }
+    for &(name, value) in tags {
+        if !header.iter().any(|&(tag, _)| tag == name) {
+            header.push((name, value.to_string()));
+        }
+    }
+
+    let mut pgn = String::new();
+    for (name, value) in header {
+        pgn.push_str(&format!("[{} \"{}\"]\n", name, value.replace('\\', "\\\\").replace('"', "\\\"")));
+    }
+    pgn.push('\n');
+
+    // A variation's parentheses stick to the moves they enclose
+    let mut words: Vec<String> = Vec::new();
+    let mut open = false;
+    for token in &movetext {
+        match token.as_str() {
+            "(" => open = true,
+            ")" => words.last_mut().expect("a variation holds a move").push(')'),
+            // Comments may break across lines, a move stays with its number
+            _ => {
+                let parts: Vec<&str> = if token.starts_with('{') { token.split_whitespace().collect() } else { vec![token] };
+                for word in parts {
+                    words.push(if open { format!("({}", word) } else { word.to_string() });
+                    open = false;
+                }
+            }
+        }
+    }
+    words.push(result.to_string());
+
+    let mut line_len = 0;
+    for word in words {
+        if line_len > 0 && line_len + 1 + word.len() > LINE_WIDTH {
+            pgn.push('\n');
+            line_len = 0;
+        } else if line_len > 0 {
+            pgn.push(' ');
+            line_len += 1;
+        }
+        line_len += word.len();
+        pgn.push_str(&word);
+    }
+    pgn.push('\n');
+    pgn
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PgnErrorKind {
+    /// A tag pair that isn't `[Name "value"]`.
+    InvalidTag(String),
+    UnterminatedComment,
+    /// A `$` NAG that isn't a number from 0 to 255, at a character offset into the text.
+    InvalidNag { nag: String, offset: usize },
+    /// A `(` or `)` without its partner, or a variation with no move to replace.
+    UnbalancedVariation,
+    InvalidFen(FenError),
+    /// A move that doesn't parse or isn't legal in the position reached.
+    IllegalMove(SanError),
+    /// The text holds no game at all.
+    NoGame,
+}
+
+/// Why a PGN text was rejected. `game` is the index of the game in the text, counting
+/// from 0, and `ply` the half-move the offending move would have been, counting from 1.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PgnError {
+    pub game: usize,
+    pub ply: Option<usize>,
+    pub kind: PgnErrorKind,
+}
+
+impl fmt::Display for PgnError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.kind {
+            PgnErrorKind::InvalidTag(tag) => write!(f, "invalid tag pair '{}'", tag)?,
+            PgnErrorKind::UnterminatedComment => f.write_str("comment is never closed")?,
+            PgnErrorKind::InvalidNag { nag, offset } => write!(f, "invalid NAG '{}' at offset {}", nag, offset)?,
+            PgnErrorKind::UnbalancedVariation => f.write_str("unbalanced variation")?,
+            PgnErrorKind::InvalidFen(e) => write!(f, "invalid FEN tag: {}", e)?,
+            PgnErrorKind::IllegalMove(e) => write!(f, "{}", e)?,
+            PgnErrorKind::NoGame => f.write_str("no game found")?,
+        }
+        write!(f, " in game {}", self.game)?;
+        if let Some(ply) = self.ply {
+            write!(f, " at ply {}", ply)?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for PgnError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match &self.kind {
+            PgnErrorKind::InvalidFen(e) => Some(e),
+            PgnErrorKind::IllegalMove(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+/// One game read from a PGN text.
+#[derive(Debug, Clone)]
+pub struct PgnGame {
+    pub tags: Vec<(String, String)>,
+    /// The position at the end of the main line.
+    pub game: ChessGame,
+    /// Every move of the game, with variations and annotations.
+    pub tree: GameTree,
+    /// The result token ending the movetext, "*" when it was missing.
+    pub result: String,
+}
+
+impl PgnGame {
+    /// Every game in a PGN text, in order.
+    pub fn read_all(pgn: &str) -> Result<Vec<PgnGame>, PgnError> {
+        let mut reader = Reader::new(pgn);
+        let mut games = Vec::new();
+        while let Some(game) = reader.next_game()? {
+            games.push(game);
+            reader.game += 1;
+        }
+        Ok(games)
+    }
+
+    /// The first game in a PGN text.
+    pub fn read_first(pgn: &str) -> Result<PgnGame, PgnError> {
+        Reader::new(pgn).next_game()?.ok_or(PgnError { game: 0, ply: None, kind: PgnErrorKind::NoGame })
+    }
+
+    pub fn tag(&self, name: &str) -> Option<&str> {
+        self.tags.iter().find(|(tag, _)| tag == name).map(|(_, value)| value.as_str())
+    }
+}
+
+impl ChessGame {
+    /// The first game of a PGN text, with its moves as the history.
+    pub fn from_pgn(pgn: &str) -> Result<ChessGame, PgnError> {
+        Ok(PgnGame::read_first(pgn)?.game)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token<'a> {
+    Tag(&'a str),
+    Move(&'a str),
+    Comment(&'a str),
+    Nag(u8),
+    Result(&'a str),
+    Open,
+    Close,
+}
+
+struct Tokens<'a> {
+    text: &'a str,
+    pos: usize,
+    line_start: bool,
+}
+
+impl<'a> Tokens<'a> {
+    /// The next token of the text. Move numbers and `%` escape lines are skipped over.
+    fn next(&mut self) -> Result<Option<Token<'a>>, PgnErrorKind> {
+        loop {
+            let rest = &self.text[self.pos..];
+            let ch = match rest.chars().next() {
+                Some(ch) => ch,
+                None => return Ok(None),
+            };
+            let line_start = self.line_start;
+            self.line_start = ch == '\n';
+
+            if ch == '%' && line_start {
+                self.pos += rest.find('\n').unwrap_or(rest.len());
+                continue;
+            }
+            if ch == ';' {
+                let end = rest.find('\n').unwrap_or(rest.len());
+                self.pos += end;
+                return Ok(Some(Token::Comment(&rest[1..end])));
+            }
+            if ch.is_whitespace() {
+                self.pos += ch.len_utf8();
+                continue;
+            }
+            match ch {
+                '{' => {
+                    let end = rest.find('}').ok_or(PgnErrorKind::UnterminatedComment)?;
+                    self.pos += end + 1;
+                    return Ok(Some(Token::Comment(&rest[1..end])));
+                }
+                '[' => {
+                    let end = tag_end(rest).ok_or_else(|| PgnErrorKind::InvalidTag(rest.lines().next().unwrap_or("").to_string()))?;
+                    self.pos += end + 1;
+                    return Ok(Some(Token::Tag(&rest[..=end])));
+                }
+                '(' => {
+                    self.pos += 1;
+                    return Ok(Some(Token::Open));
+                }
+                ')' => {
+                    self.pos += 1;
+                    return Ok(Some(Token::Close));
+                }
+                _ => {
+                    let start = self.pos;
+                    let len = rest.find(|c: char| c.is_whitespace() || "{}[]();".contains(c)).unwrap_or(rest.len());
+                    self.pos += len;
+                    let symbol = &rest[..len];
+                    if let "1-0" | "0-1" | "1/2-1/2" | "*" = symbol {
+                        return Ok(Some(Token::Result(symbol)));
+                    }
+                    if let Some(nag) = symbol.strip_prefix('$') {
+                        // `parse` alone would also take a sign
+                        return match nag.parse() {
+                            Ok(nag) if symbol[1..].bytes().all(|b| b.is_ascii_digit()) => Ok(Some(Token::Nag(nag))),
+                            _ => Err(PgnErrorKind::InvalidNag { nag: symbol.to_string(), offset: self.text[..start].chars().count() }),
+                        };
+                    }
+                    if let Some(nag) = nag_for_glyph(symbol) {
+                        return Ok(Some(Token::Nag(nag)));
+                    }
+                    // "12", "12." and "12..." as well as "1.e4"
+                    let digits = symbol.len() - symbol.trim_start_matches(|c: char| c.is_ascii_digit()).len();
+                    let san = if digits > 0 && (digits == symbol.len() || symbol[digits..].starts_with('.')) {
+                        symbol[digits..].trim_start_matches('.')
+                    } else {
+                        symbol
+                    };
+                    if !san.is_empty() {
+                        return Ok(Some(Token::Move(san)));
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Byte offset of the `]` closing the tag pair `text` starts with, skipping over the quoted value.
+fn tag_end(text: &str) -> Option<usize> {
+    let mut in_string = false;
+    let mut escaped = false;
+    for (i, ch) in text.char_indices() {
+        match ch {
+            _ if escaped => escaped = false,
+            '\\' if in_string => escaped = true,
+            '"' => in_string = !in_string,
+            ']' if !in_string => return Some(i),
+            '\n' => return None,
+            _ => {}
+        }
+    }
+    None
+}
+
+/// `[Name "value"]` split into the name and the unescaped value.
+fn parse_tag(tag: &str) -> Option<(String, String)> {
+    let inner = tag.strip_prefix('[')?.strip_suffix(']')?.trim();
+    let (name, value) = inner.split_once(char::is_whitespace)?;
+    let value = value.trim().strip_prefix('"')?.strip_suffix('"')?;
+    if name.is_empty() || !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+        return None;
+    }
+    let mut unescaped = String::new();
+    let mut chars = value.chars();
+    while let Some(ch) = chars.next() {
+        unescaped.push(if ch == '\\' { chars.next()? } else { ch });
+    }
+    Some((name.to_string(), unescaped))
+}
+
+struct Reader<'a> {
+    tokens: Tokens<'a>,
+    peeked: Option<Token<'a>>,
+    game: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn new(text: &'a str) -> Reader<'a> {
+        Reader { tokens: Tokens { text, pos: 0, line_start: true }, peeked: None, game: 0 }
+    }
+
+    fn error(&self, ply: Option<usize>, kind: PgnErrorKind) -> PgnError {
+        PgnError { game: self.game, ply, kind }
+    }
+
+    fn next_token(&mut self) -> Result<Option<Token<'a>>, PgnError> {
+        match self.peeked.take() {
+            Some(token) => Ok(Some(token)),
+            None => self.tokens.next().map_err(|kind| self.error(None, kind)),
+        }
+    }
+
+    fn next_game(&mut self) -> Result<Option<PgnGame>, PgnError> {
+        let mut tags = Vec::new();
+        // Comments around the tags, such as one left after the previous game's result
+        let mut comments = Vec::new();
+        let mut token = self.next_token()?;
+        loop {
+            match token {
+                Some(Token::Tag(tag)) => {
+                    tags.push(parse_tag(tag).ok_or_else(|| self.error(None, PgnErrorKind::InvalidTag(tag.to_string())))?);
+                }
+                Some(Token::Comment(text)) => comments.push(text),
+                _ => break,
+            }
+            token = self.next_token()?;
+        }
+        if token.is_none() && tags.is_empty() {
+            return Ok(None);
+        }
+        self.peeked = token;
+
+        let mut start = ChessGame::new();
+        if let Some((_, fen)) = tags.iter().find(|(name, _)| name == "FEN") {
+            start = ChessGame::from_fen(fen).map_err(|e| self.error(None, PgnErrorKind::InvalidFen(e)))?;
+        }
+        if tags.iter().any(|(name, value)| name == "Variant" && value.eq_ignore_ascii_case("chess960")) {
+            start.chess960 = true;
+        }
+        let mut tree = GameTree::new(start);
+        for text in comments {
+            tree.annotation_mut(0).expect("the root is never deleted").add_comment(text);
+        }
+        let result = self.movetext(&mut tree, 0, false)?;
+        // The token wins over the tag, which stands in when the token is missing
+        let tag = tags.iter().find(|(name, _)| name == "Result").map(|(_, value)| value.as_str());
+        let recorded = Some(result).filter(|&result| result != "*").or(tag);
+        if let Some(recorded) = recorded {
+            tree.record_result(recorded);
+        }
+        let mainline = tree.mainline();
+        let end = *mainline.last().expect("starts with the root");
+        let mut game = tree.node(end).expect("on the main line").game().clone();
+        game.annotations = mainline.iter().map(|&id| tree.node(id).expect("on the main line").annotation().clone()).collect();
+        Ok(Some(PgnGame { tags, game, tree, result: result.to_string() }))
+    }
+
+    /// Adds the moves of a line to `tree` after `node`, up to the line's end: the result
+    /// token or the end of the text for the main line, the closing `)` for a variation.
+    fn movetext(&mut self, tree: &mut GameTree, mut node: usize, variation: bool) -> Result<&'a str, PgnError> {
+        loop {
+            match self.next_token()? {
+                Some(Token::Move(san)) => {
+                    let game = tree.node(node).expect("added by this line").game();
+                    let ply = Some(game.ply_count() + 1);
+                    let mv = game.parse_san(san).map_err(|e| self.error(ply, PgnErrorKind::IllegalMove(e)))?;
+                    node = tree.add_move(node, mv);
+                    // "e4!?" carries its NAG along
+                    let glyph = &san[san.trim_end_matches(['!', '?']).len()..];
+                    if let Some(nag) = nag_for_glyph(glyph) {
+                        tree.add_nag(node, nag);
+                    }
+                }
+                // Before the first move of a line they go to the position it starts from
+                Some(Token::Comment(text)) => {
+                    tree.annotation_mut(node).expect("added by this line").add_comment(text);
+                }
+                Some(Token::Nag(nag)) => {
+                    tree.add_nag(node, nag);
+                }
+                // A variation replaces the last move, so it branches off before it
+                Some(Token::Open) => {
+                    let parent = tree.parent_of(node).ok_or_else(|| self.error(None, PgnErrorKind::UnbalancedVariation))?;
+                    self.movetext(tree, parent, true)?;
+                }
+                Some(Token::Close) if variation => return Ok("*"),
+                Some(Token::Result(result)) if !variation => return Ok(result),
+                None if !variation => return Ok("*"),
+                // The next game's tags, when a game ends without a result token
+                Some(tag @ Token::Tag(_)) if !variation => {
+                    self.peeked = Some(tag);
+                    return Ok("*");
+                }
+                _ => return Err(self.error(None, PgnErrorKind::UnbalancedVariation)),
+            }
+        }
+    }
+}
@@ -0,0 +1,226 @@
+use std::fmt;
+
+#[cfg(feature = "wasm")]
+use wasm_bindgen::prelude::*;
+
+use crate::error::{ChessError, PromotionError};
+use crate::movegen::Move;
+use crate::types::{Piece, PieceKind, Square};
+use crate::ChessGame;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SanError {
+    /// The text isn't shaped like a SAN move.
+    Malformed(String),
+    /// Well-formed, but no legal move in the position fits it.
+    NoLegalMove(String),
+    /// More than one legal move fits; the text needs a disambiguating file or rank.
+    Ambiguous(String),
+}
+
+impl fmt::Display for SanError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SanError::Malformed(san) => write!(f, "'{}' is not a SAN move", san),
+            SanError::NoLegalMove(san) => write!(f, "'{}' does not match any legal move", san),
+            SanError::Ambiguous(san) => write!(f, "'{}' matches more than one legal move", san),
+        }
+    }
+}
+
+impl std::error::Error for SanError {}
+
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+impl ChessGame {
+    /// The SAN of the legal move from (start_x, start_y) to (end_x, end_y).
+    /// `promotion` is the piece code a pawn promotes to, as in `make_move`.
+    pub fn move_san(&self, start_x: usize, start_y: usize, end_x: usize, end_y: usize, promotion: Option<i32>) -> Result<String, ChessError> {
+        let from = Square::new(start_x, start_y).ok_or(ChessError::OffBoard { x: start_x, y: start_y })?;
+        let to = Square::new(end_x, end_y).ok_or(ChessError::OffBoard { x: end_x, y: end_y })?;
+        let promotion = promotion.and_then(Piece::from_code).map(|piece| piece.kind);
+        let mv = self.find_legal_move(from, to, promotion)?;
+        Ok(self.san(&mv))
+    }
+
+    /// Plays a move given in SAN, such as "Nbd2", "exd6", "O-O-O" or "e8=Q#".
+    pub fn play_san(&mut self, san: &str) -> Result<(), ChessError> {
+        if self.termination.is_some() {
+            return Err(ChessError::GameOver);
+        }
+        if self.pending_promotion.is_some() {
+            return Err(ChessError::PromotionPending);
+        }
+        let mv = self.parse_san(san)?;
+        self.make(mv.from, mv.to, mv.promotion);
+        Ok(())
+    }
+}
+
+impl ChessGame {
+    /// The SAN of `mv`, which must be legal in the current position.
+    pub fn san(&self, mv: &Move) -> String {
+        self.clone().san_in_place(mv)
+    }
+
+    /// `san` playing the move on this game to see whether it checks, then taking it
+    /// back, so a caller writing many moves needs only one copy of the position.
+    pub(crate) fn san_in_place(&mut self, mv: &Move) -> String {
+        let mut san = String::new();
+        if mv.is_castle {
+            san.push_str(if mv.to.y() > mv.from.y() { "O-O" } else { "O-O-O" });
+        } else {
+            let is_capture = mv.captured.is_some();
+            if mv.piece.kind == PieceKind::Pawn {
+                if is_capture {
+                    san.push(mv.from.file_char());
+                }
+            } else {
+                san.push(mv.piece.kind.letter());
+                san.push_str(&self.disambiguation(mv));
+            }
+            if is_capture {
+                san.push('x');
+            }
+            san.push_str(&mv.to.to_string());
+            if let Some(kind) = mv.promotion {
+                san.push('=');
+                san.push(kind.letter());
+            }
+        }
+
+        match self.check_after(mv) {
+            (true, true) => san.push('#'),
+            (true, false) => san.push('+'),
+            _ => {}
+        }
+        san
+    }
+
+    /// Whether `mv` gives check and whether it mates. The move is played and taken
+    /// back, leaving the game as it was, redo stack included.
+    pub(crate) fn check_after(&mut self, mv: &Move) -> (bool, bool) {
+        let undo = self.apply(mv.from, mv.to, mv.promotion);
+        let check = self.in_check(self.current_turn);
+        let mate = check && self.legal_moves().is_empty();
+        self.retract(undo);
+        (check, mate)
+    }
+
+    /// Resolves a SAN string to the legal move it names. Check, mate and annotation
+    /// suffixes ("+", "#", "!", "?") are accepted but not required, and so is "0-0".
+    pub fn parse_san(&self, san: &str) -> Result<Move, SanError> {
+        let malformed = || SanError::Malformed(san.to_string());
+        let text = san.trim().trim_end_matches(['+', '#', '!', '?']);
+        let legal = self.legal_moves();
+
+        let castle = match text {
+            "O-O" | "0-0" => Some(true),
+            "O-O-O" | "0-0-0" => Some(false),
+            _ => None,
+        };
+        let candidates: Vec<Move> = if let Some(kingside) = castle {
+            legal.into_iter()
+                .filter(|mv| mv.is_castle && (mv.to.y() > mv.from.y()) == kingside)
+                .collect()
+        } else {
+            let mut chars: Vec<char> = text.chars().collect();
+
+            let kind = match chars.first() {
+                Some(&letter) if letter.is_ascii_uppercase() => {
+                    chars.remove(0);
+                    PieceKind::from_letter(letter).filter(|&kind| kind != PieceKind::Pawn).ok_or_else(malformed)?
+                }
+                _ => PieceKind::Pawn,
+            };
+
+            // "=Q" or a bare "Q" after the target square
+            let promotion = match chars.last() {
+                Some(&letter) if letter.is_ascii_uppercase() => {
+                    chars.pop();
+                    if chars.last() == Some(&'=') {
+                        chars.pop();
+                    }
+                    Some(PieceKind::from_letter(letter).filter(|kind| PieceKind::PROMOTIONS.contains(kind)).ok_or_else(malformed)?)
+                }
+                _ => None,
+            };
+
+            if chars.len() < 2 {
+                return Err(malformed());
+            }
+            let target: String = chars.split_off(chars.len() - 2).into_iter().collect();
+            let to = Square::from_algebraic(&target).ok_or_else(malformed)?;
+            if chars.last() == Some(&'x') {
+                chars.pop();
+            }
+
+            // Whatever is left disambiguates: a file, a rank, or both
+            let (mut file, mut rank) = (None, None);
+            for ch in chars {
+                match ch {
+                    'a'..='h' if file.is_none() && rank.is_none() => file = Some(ch),
+                    '1'..='8' if rank.is_none() => rank = Some(ch),
+                    _ => return Err(malformed()),
+                }
+            }
+            // A pawn move names its file only when capturing
+            if kind == PieceKind::Pawn && rank.is_some() {
+                return Err(malformed());
+            }
+
+            legal.into_iter()
+                .filter(|mv| {
+                    mv.piece.kind == kind
+                        && mv.to == to
+                        && !mv.is_castle
+                        && mv.promotion == promotion
+                        && file.is_none_or(|file| mv.from.file_char() == file)
+                        && rank.is_none_or(|rank| mv.from.rank_char() == rank)
+                })
+                .collect()
+        };
+
+        match candidates.as_slice() {
+            [mv] => Ok(*mv),
+            [] => Err(SanError::NoLegalMove(san.to_string())),
+            _ => Err(SanError::Ambiguous(san.to_string())),
+        }
+    }
+
+    /// The legal move from `from` to `to`; promotions need the piece.
+    pub(crate) fn find_legal_move(&self, from: Square, to: Square, promotion: Option<PieceKind>) -> Result<Move, ChessError> {
+        self.legal_moves()
+            .into_iter()
+            .find(|mv| mv.from == from && mv.to == to && mv.promotion == promotion)
+            .ok_or_else(|| {
+                let reason = self.explain_move(from, to);
+                // Otherwise the move itself is fine and only the promotion piece is off
+                let promotes = self.piece_at(from).map(|piece| piece.kind) == Some(PieceKind::Pawn) && (to.x() == 0 || to.x() == 7);
+                match (reason, promotion) {
+                    (Err(reason), _) => ChessError::IllegalMove { from, to, reason },
+                    (Ok(()), None) => PromotionError::MissingPiece.into(),
+                    (Ok(()), Some(_)) if !promotes => PromotionError::NotAPromotion.into(),
+                    (Ok(()), Some(_)) => PromotionError::InvalidPiece.into(),
+                }
+            })
+    }
+
+    /// The file, rank or square needed to tell `mv` apart from other moves of the same
+    /// kind of piece to the same square.
+    fn disambiguation(&self, mv: &Move) -> String {
+        let rivals: Vec<Square> = self.legal_moves()
+            .into_iter()
+            .filter(|other| other.piece == mv.piece && other.to == mv.to && other.from != mv.from)
+            .map(|other| other.from)
+            .collect();
+        if rivals.is_empty() {
+            String::new()
+        } else if rivals.iter().all(|sq| sq.y() != mv.from.y()) {
+            mv.from.file_char().to_string()
+        } else if rivals.iter().all(|sq| sq.x() != mv.from.x()) {
+            mv.from.rank_char().to_string()
+        } else {
+            mv.from.to_string()
+        }
+    }
+}
@@ -0,0 +1,190 @@
+use std::time::{Duration, Instant};
+
+#[cfg(feature = "wasm")]
+use wasm_bindgen::prelude::*;
+
+use crate::bitboard::squares;
+use crate::movegen::Move;
+use crate::types::{Color, PieceKind};
+use crate::ChessGame;
+
+/// Scores above `MATE - MAX_PLY` are mates, the difference being the plies to go.
+pub const MATE: i32 = 100_000;
+const MAX_PLY: i32 = 1_000;
+
+/// What `search` settled on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SearchResult {
+    pub best_move: Move,
+    /// Centipawns for the side to move, or a mate score, see `MATE`.
+    pub score: i32,
+    /// The deepest iteration that finished.
+    pub depth: u32,
+    pub nodes: u64,
+}
+
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+impl ChessGame {
+    /// The best move found searching `depth` plies, in UCI notation, or `None` when
+    /// there is no legal move.
+    pub fn best_move_uci(&self, depth: u32) -> Option<String> {
+        self.search(depth, None).map(|result| self.to_uci(&result.best_move))
+    }
+}
+
+impl ChessGame {
+    /// Alpha-beta search to `depth` plies, deepening one ply at a time. With a `time`
+    /// budget the search stops when it runs out and keeps the last finished depth,
+    /// though depth 1 always finishes. Time budgets need a clock, which wasm32 lacks.
+    pub fn search(&self, depth: u32, time: Option<Duration>) -> Option<SearchResult> {
+        let deadline = time.map(|time| Instant::now() + time);
+        // The clock only starts to matter after the first depth
+        let mut searcher = Searcher { game: self.clone(), nodes: 0, deadline: None, stopped: false };
+        let mut moves = self.legal_moves();
+        order_moves(&mut moves);
+        let mut best: Option<SearchResult> = None;
+
+        for iteration in 1..=depth.max(1) {
+            let mut alpha = -MATE - 1;
+            let mut iteration_best = None;
+            for &mv in &moves {
+                let undo = searcher.game.make(mv.from, mv.to, mv.promotion);
+                let score = -searcher.negamax(iteration - 1, 1, -MATE - 1, -alpha);
+                searcher.game.unmake(undo);
+                // A depth that didn't finish can't be trusted
+                if searcher.stopped {
+                    break;
+                }
+                if score > alpha {
+                    alpha = score;
+                    iteration_best = Some(mv);
+                }
+            }
+            if searcher.stopped {
+                break;
+            }
+            // No legal moves at all
+            let best_move = iteration_best?;
+            best = Some(SearchResult { best_move, score: alpha, depth: iteration, nodes: searcher.nodes });
+            // Search the best move first next time round
+            let index = moves.iter().position(|&mv| mv == best_move).expect("chosen from these moves");
+            let first = moves.remove(index);
+            moves.insert(0, first);
+            if alpha.abs() > MATE - MAX_PLY {
+                break;
+            }
+            searcher.deadline = deadline;
+        }
+        best.map(|result| SearchResult { nodes: searcher.nodes, ..result })
+    }
+
+    /// A static score of the position for the side to move, in centipawns.
+    pub fn evaluate(&self) -> i32 {
+        let mut score = 0;
+        for &color in &[Color::White, Color::Black] {
+            let sign = if color == self.current_turn { 1 } else { -1 };
+            for &kind in &PieceKind::ALL {
+                for sq in squares(self.board.pieces(kind, color)) {
+                    let (x, y) = ((sq / 8) as i32, (sq % 8) as i32);
+                    // Rows still to go for a pawn, distance from the centre for the others
+                    let advance = if color == Color::White { 6 - x } else { x - 1 };
+                    let centre = (2 * x - 7).abs().max((2 * y - 7).abs());
+                    score += sign * match kind {
+                        PieceKind::Pawn => 100 + 5 * advance * advance,
+                        PieceKind::Knight => 320 - 5 * centre,
+                        PieceKind::Bishop => 330 - 3 * centre,
+                        PieceKind::Rook => 500,
+                        PieceKind::Queen => 900,
+                        PieceKind::King => 0,
+                    };
+                }
+            }
+        }
+        score
+    }
+}
+
+struct Searcher {
+    game: ChessGame,
+    nodes: u64,
+    deadline: Option<Instant>,
+    stopped: bool,
+}
+
+impl Searcher {
+    fn out_of_time(&mut self) -> bool {
+        self.nodes += 1;
+        if !self.stopped && self.nodes.is_multiple_of(1024) {
+            self.stopped = self.deadline.is_some_and(|deadline| Instant::now() >= deadline);
+        }
+        self.stopped
+    }
+
+    fn negamax(&mut self, depth: u32, ply: i32, mut alpha: i32, beta: i32) -> i32 {
+        if self.out_of_time() {
+            return 0;
+        }
+        let mut moves = self.game.legal_moves();
+        if moves.is_empty() {
+            return if self.game.in_check(self.game.current_turn) { -MATE + ply } else { 0 };
+        }
+        if self.game.halfmove_clock >= 100 {
+            return 0;
+        }
+        if depth == 0 {
+            return self.quiesce(alpha, beta);
+        }
+        order_moves(&mut moves);
+        for mv in moves {
+            let undo = self.game.make(mv.from, mv.to, mv.promotion);
+            let score = -self.negamax(depth - 1, ply + 1, -beta, -alpha);
+            self.game.unmake(undo);
+            if score >= beta {
+                return beta;
+            }
+            alpha = alpha.max(score);
+        }
+        alpha
+    }
+
+    /// Plays out captures and promotions so the score isn't taken mid-exchange.
+    fn quiesce(&mut self, mut alpha: i32, beta: i32) -> i32 {
+        let stand_pat = self.game.evaluate();
+        if stand_pat >= beta {
+            return beta;
+        }
+        alpha = alpha.max(stand_pat);
+
+        let mut moves: Vec<Move> = self.game.legal_moves().into_iter().filter(|mv| mv.captured.is_some() || mv.promotion.is_some()).collect();
+        order_moves(&mut moves);
+        for mv in moves {
+            if self.out_of_time() {
+                return 0;
+            }
+            let undo = self.game.make(mv.from, mv.to, mv.promotion);
+            let score = -self.quiesce(-beta, -alpha);
+            self.game.unmake(undo);
+            if score >= beta {
+                return beta;
+            }
+            alpha = alpha.max(score);
+        }
+        alpha
+    }
+}
+
+/// Promotions and captures first, the most valuable victim taken by the cheapest piece leading.
+fn order_moves(moves: &mut [Move]) {
+    let value = |kind: PieceKind| match kind {
+        PieceKind::Pawn => 1,
+        PieceKind::Knight | PieceKind::Bishop => 3,
+        PieceKind::Rook => 5,
+        PieceKind::Queen => 9,
+        PieceKind::King => 10,
+    };
+    moves.sort_by_key(|mv| {
+        let promotion = mv.promotion.map_or(0, value) * 100;
+        let capture = mv.captured.map_or(0, |piece| 10 * value(piece.kind) - value(mv.piece.kind) + 10);
+        -(promotion + capture)
+    });
+}
@@ -0,0 +1,257 @@
+use std::fmt;
+
+use serde::ser::{Serialize, SerializeStruct, Serializer};
+#[cfg(feature = "wasm")]
+use wasm_bindgen::prelude::*;
+
+use crate::bitboard::squares;
+use crate::board::Board;
+use crate::chess960::{castling_index, home_rank, outermost_rook};
+use crate::error::ChessError;
+use crate::types::{Color, Piece, PieceKind, Square};
+use crate::ChessGame;
+
+/// Something that makes a position impossible to reach, see `validate_position`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PositionProblem {
+    MissingKing(Color),
+    TooManyKings(Color),
+    TooManyPawns(Color),
+    /// More than sixteen pieces, pawns and king included.
+    TooManyPieces(Color),
+    /// A pawn on the first or eighth rank.
+    PawnOnBackRank(Square),
+    /// The side that just moved left its king in check.
+    OpponentInCheck,
+    /// A castling right whose king or rook isn't on its home square.
+    ImpossibleCastling { color: Color, kingside: bool },
+    /// An en passant square that no double push of the side that just moved can explain.
+    ImpossibleEnPassant(Square),
+}
+
+impl PositionProblem {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PositionProblem::MissingKing(_) => "missing_king",
+            PositionProblem::TooManyKings(_) => "too_many_kings",
+            PositionProblem::TooManyPawns(_) => "too_many_pawns",
+            PositionProblem::TooManyPieces(_) => "too_many_pieces",
+            PositionProblem::PawnOnBackRank(_) => "pawn_on_back_rank",
+            PositionProblem::OpponentInCheck => "opponent_in_check",
+            PositionProblem::ImpossibleCastling { .. } => "impossible_castling",
+            PositionProblem::ImpossibleEnPassant(_) => "impossible_en_passant",
+        }
+    }
+
+    /// The square the problem refers to, if any.
+    pub fn square(&self) -> Option<Square> {
+        match self {
+            PositionProblem::PawnOnBackRank(sq) | PositionProblem::ImpossibleEnPassant(sq) => Some(*sq),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for PositionProblem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PositionProblem::MissingKing(color) => write!(f, "{} has no king.", color),
+            PositionProblem::TooManyKings(color) => write!(f, "{} has more than one king.", color),
+            PositionProblem::TooManyPawns(color) => write!(f, "{} has more than eight pawns.", color),
+            PositionProblem::TooManyPieces(color) => write!(f, "{} has more than sixteen pieces.", color),
+            PositionProblem::PawnOnBackRank(sq) => write!(f, "The pawn on {} can't stand on the first or last rank.", sq),
+            PositionProblem::OpponentInCheck => f.write_str("The side not to move is in check."),
+            PositionProblem::ImpossibleCastling { color, kingside } => {
+                let side = if *kingside { "kingside" } else { "queenside" };
+                write!(f, "{} can't castle {} with the king and rooks where they are.", color, side)
+            }
+            PositionProblem::ImpossibleEnPassant(sq) => write!(f, "No pawn can just have passed over {}.", sq),
+        }
+    }
+}
+
+// Serialized as {"problem": "pawn_on_back_rank", "square": [0, 3], "message": "..."}
+impl Serialize for PositionProblem {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut state = serializer.serialize_struct("PositionProblem", 3)?;
+        state.serialize_field("problem", self.as_str())?;
+        state.serialize_field("square", &self.square())?;
+        state.serialize_field("message", &self.to_string())?;
+        state.end()
+    }
+}
+
+// Board editing. Every edit starts the game over from the edited position, with no moves
+// to undo, and drops an en passant square the edit made impossible. Other impossible
+// positions are left for `validate_position` to report.
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+impl ChessGame {
+    /// Places a piece, in the i32 encoding from `constants`, on (x, y), replacing whatever stood there.
+    pub fn put_piece(&mut self, x: usize, y: usize, piece: i32) -> Result<(), ChessError> {
+        let sq = Square::new(x, y).ok_or(ChessError::OffBoard { x, y })?;
+        let piece = Piece::from_code(piece).ok_or(ChessError::UnknownPiece(piece))?;
+        self.board.set_piece(sq, piece);
+        self.finish_edit();
+        Ok(())
+    }
+
+    /// Empties (x, y) and returns the code of the piece that stood there, if any.
+    pub fn remove_piece(&mut self, x: usize, y: usize) -> Result<Option<i32>, ChessError> {
+        let sq = Square::new(x, y).ok_or(ChessError::OffBoard { x, y })?;
+        let piece = self.board.piece_at(sq);
+        self.board.remove_piece(sq);
+        self.finish_edit();
+        Ok(piece.map(Piece::code))
+    }
+
+    /// Removes every piece and all castling rights.
+    pub fn clear_board(&mut self) {
+        self.board = Board::empty();
+        for index in 0..4 {
+            self.set_castling_right(index, false);
+        }
+        self.en_passant_target = None;
+        self.reset_history();
+    }
+
+    /// Sets the side to move: 1 for White, 2 for Black.
+    pub fn set_turn(&mut self, color: i32) -> Result<(), ChessError> {
+        self.current_turn = Color::from_code(color).ok_or(ChessError::UnknownColor(color))?;
+        self.finish_edit();
+        Ok(())
+    }
+
+    /// Grants or takes away one castling right. In Chess960 the right goes to the outermost
+    /// rook on that side of the king.
+    pub fn set_castling(&mut self, color: i32, kingside: bool, allowed: bool) -> Result<(), ChessError> {
+        let color = Color::from_code(color).ok_or(ChessError::UnknownColor(color))?;
+        let index = castling_index(color, kingside);
+        if allowed {
+            let king_file = self.board.king_square(color).filter(|king| king.x() == home_rank(color)).map(|king| king.y());
+            let outermost = king_file.and_then(|king| outermost_rook(&self.board, color, king, kingside));
+            self.castling_rook_files[index] = match outermost {
+                Some(file) if self.chess960 => file,
+                _ if kingside => 7,
+                _ => 0,
+            };
+        }
+        self.set_castling_right(index, allowed);
+        self.reset_history();
+        Ok(())
+    }
+
+    /// Sets the square a pawn just passed over, which a pawn of the side to move may capture on.
+    /// Refused unless a pawn of the other side stands right in front of it, with the square
+    /// and the one the pawn came from empty.
+    pub fn set_en_passant(&mut self, x: usize, y: usize) -> Result<(), ChessError> {
+        let target = Square::new(x, y).ok_or(ChessError::OffBoard { x, y })?;
+        if !self.en_passant_possible(target) {
+            return Err(ChessError::ImpossibleEnPassant(target));
+        }
+        self.en_passant_target = Some(target);
+        self.reset_history();
+        Ok(())
+    }
+
+    pub fn clear_en_passant(&mut self) {
+        self.en_passant_target = None;
+        self.reset_history();
+    }
+
+    /// Switches between the standard castling rules and the Chess960 ones.
+    pub fn set_chess960(&mut self, chess960: bool) {
+        self.chess960 = chess960;
+        self.reset_history();
+    }
+
+    /// Every problem found by `validate_position`, serialized as a JSON array of
+    /// `{"problem": ..., "square": ..., "message": ...}`. Empty for a legal position.
+    pub fn validate_position_json(&self) -> String {
+        match serde_json::to_string(&self.validate_position()) {
+            Ok(json) => json,
+            Err(e) => format!("{{\"error\": \"{}\"}}", e),
+        }
+    }
+}
+
+impl ChessGame {
+    /// Everything that keeps the position from being a legal one: the kings, piece counts
+    /// and pawns first, then check, castling rights and the en passant square.
+    pub fn validate_position(&self) -> Vec<PositionProblem> {
+        let mut problems = Vec::new();
+        for &color in &[Color::White, Color::Black] {
+            match self.board.pieces(PieceKind::King, color).count_ones() {
+                0 => problems.push(PositionProblem::MissingKing(color)),
+                1 => {}
+                _ => problems.push(PositionProblem::TooManyKings(color)),
+            }
+        }
+        for &color in &[Color::White, Color::Black] {
+            if self.board.pieces(PieceKind::Pawn, color).count_ones() > 8 {
+                problems.push(PositionProblem::TooManyPawns(color));
+            }
+            if self.board.by_color(color).count_ones() > 16 {
+                problems.push(PositionProblem::TooManyPieces(color));
+            }
+        }
+        for sq in squares(self.board.by_kind(PieceKind::Pawn)).map(Square::from_index) {
+            if sq.x() == 0 || sq.x() == 7 {
+                problems.push(PositionProblem::PawnOnBackRank(sq));
+            }
+        }
+
+        let opponent = self.current_turn.opponent();
+        let opponent_in_check = squares(self.board.pieces(PieceKind::King, opponent))
+            .any(|king| self.board.is_square_attacked(Square::from_index(king), self.current_turn));
+        if opponent_in_check {
+            problems.push(PositionProblem::OpponentInCheck);
+        }
+
+        for (index, &(color, kingside)) in [(Color::White, true), (Color::White, false), (Color::Black, true), (Color::Black, false)].iter().enumerate() {
+            if self.castling_right(index) && !self.castling_possible(color, kingside) {
+                problems.push(PositionProblem::ImpossibleCastling { color, kingside });
+            }
+        }
+        if let Some(target) = self.en_passant_target {
+            if !self.en_passant_possible(target) {
+                problems.push(PositionProblem::ImpossibleEnPassant(target));
+            }
+        }
+        problems
+    }
+
+    /// King and rook still stand where a castling right says they started.
+    fn castling_possible(&self, color: Color, kingside: bool) -> bool {
+        let home_rank = home_rank(color);
+        let rook_file = self.castling_rook_files[castling_index(color, kingside)];
+        let king_file = match self.board.king_square(color) {
+            Some(king) if king.x() == home_rank => king.y(),
+            _ => return false,
+        };
+        let rook = self.board.piece_at(Square::new(home_rank, rook_file).unwrap()) == Some(Piece::new(color, PieceKind::Rook));
+        let rook_on_its_side = if kingside { rook_file > king_file } else { rook_file < king_file };
+        let standard = king_file == 4 && rook_file == if kingside { 7 } else { 0 };
+        rook && rook_on_its_side && (self.chess960 || standard)
+    }
+
+    /// The side that just moved has a pawn right in front of `target`, and both `target`
+    /// and the square the pawn came from are empty.
+    fn en_passant_possible(&self, target: Square) -> bool {
+        let mover = self.current_turn.opponent();
+        // Rows counted from the top: White's pawns pass row 5, Black's row 2
+        let (passed, start, landed) = if mover == Color::White { (5, 6, 4) } else { (2, 1, 3) };
+        if target.x() != passed {
+            return false;
+        }
+        let at = |x: usize| self.board.piece_at(Square::new(x, target.y()).unwrap());
+        at(passed).is_none() && at(start).is_none() && at(landed) == Some(Piece::new(mover, PieceKind::Pawn))
+    }
+
+    // Move generation trusts the en passant square, so one that no longer fits has to go
+    fn finish_edit(&mut self) {
+        if self.en_passant_target.is_some_and(|target| !self.en_passant_possible(target)) {
+            self.en_passant_target = None;
+        }
+        self.reset_history();
+    }
+}
@@ -0,0 +1,190 @@
+use serde::ser::{Serialize, SerializeStruct, Serializer};
+#[cfg(feature = "wasm")]
+use wasm_bindgen::prelude::*;
+
+use crate::error::ChessError;
+use crate::types::Color;
+use crate::ChessGame;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DrawReason {
+    FiftyMoveRule,
+    SeventyFiveMoveRule,
+    ThreefoldRepetition,
+    FivefoldRepetition,
+    InsufficientMaterial,
+    DeadPosition,
+    Agreement,
+}
+
+impl DrawReason {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DrawReason::FiftyMoveRule => "fifty_move_rule",
+            DrawReason::SeventyFiveMoveRule => "seventy_five_move_rule",
+            DrawReason::ThreefoldRepetition => "threefold_repetition",
+            DrawReason::FivefoldRepetition => "fivefold_repetition",
+            DrawReason::InsufficientMaterial => "insufficient_material",
+            DrawReason::DeadPosition => "dead_position",
+            DrawReason::Agreement => "agreement",
+        }
+    }
+}
+
+/// Where the game stands.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GameStatus {
+    Ongoing,
+    Check,
+    Checkmate { winner: Color },
+    Stalemate,
+    Draw(DrawReason),
+    Resignation { winner: Color },
+    Timeout { winner: Color },
+}
+
+impl GameStatus {
+    /// The PGN result: "1-0", "0-1", "1/2-1/2", or "*" while the game goes on. The Go
+    /// server's `MoveResponse.Outcome` uses the same strings, "*" included.
+    pub fn outcome(&self) -> &'static str {
+        match self {
+            GameStatus::Ongoing | GameStatus::Check => "*",
+            GameStatus::Stalemate | GameStatus::Draw(_) => "1/2-1/2",
+            GameStatus::Checkmate { winner } | GameStatus::Resignation { winner } | GameStatus::Timeout { winner } => {
+                if *winner == Color::White { "1-0" } else { "0-1" }
+            }
+        }
+    }
+
+    pub fn is_over(&self) -> bool {
+        !matches!(self, GameStatus::Ongoing | GameStatus::Check)
+    }
+
+    pub fn winner(&self) -> Option<Color> {
+        match self {
+            GameStatus::Checkmate { winner } | GameStatus::Resignation { winner } | GameStatus::Timeout { winner } => Some(*winner),
+            _ => None,
+        }
+    }
+
+    fn name(&self) -> &'static str {
+        match self {
+            GameStatus::Ongoing => "ongoing",
+            GameStatus::Check => "check",
+            GameStatus::Checkmate { .. } => "checkmate",
+            GameStatus::Stalemate => "stalemate",
+            GameStatus::Draw(_) => "draw",
+            GameStatus::Resignation { .. } => "resignation",
+            GameStatus::Timeout { .. } => "timeout",
+        }
+    }
+}
+
+// Serialized as {"status": "checkmate", "winner": "white", "reason": null, "outcome": "1-0"}
+impl Serialize for GameStatus {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut state = serializer.serialize_struct("GameStatus", 4)?;
+        state.serialize_field("status", self.name())?;
+        state.serialize_field("winner", &self.winner().map(|winner| if winner == Color::White { "white" } else { "black" }))?;
+        state.serialize_field("reason", &match self {
+            GameStatus::Draw(reason) => Some(reason.as_str()),
+            _ => None,
+        })?;
+        state.serialize_field("outcome", self.outcome())?;
+        state.end()
+    }
+}
+
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+impl ChessGame {
+    /// The game status serialized as JSON, see `status`.
+    pub fn status_json(&self) -> String {
+        match serde_json::to_string(&self.status()) {
+            Ok(json) => json,
+            Err(e) => format!("{{\"error\": \"{}\"}}", e),
+        }
+    }
+
+    /// The result string: "1-0", "0-1", "1/2-1/2" or "*".
+    pub fn outcome(&self) -> String {
+        self.status().outcome().to_string()
+    }
+
+    /// Ends the game with `color` resigning: 1 for White, 2 for Black. Like the other
+    /// ways of ending a game off the board, refused once the game is over.
+    pub fn resign(&mut self, color: i32) -> Result<(), ChessError> {
+        let loser = Color::from_code(color).ok_or(ChessError::UnknownColor(color))?;
+        self.end_game(GameStatus::Resignation { winner: loser.opponent() })
+    }
+
+    /// Ends the game with `color` having run out of time.
+    pub fn flag_timeout(&mut self, color: i32) -> Result<(), ChessError> {
+        let loser = Color::from_code(color).ok_or(ChessError::UnknownColor(color))?;
+        self.end_game(GameStatus::Timeout { winner: loser.opponent() })
+    }
+
+    pub fn agree_draw(&mut self) -> Result<(), ChessError> {
+        self.end_game(GameStatus::Draw(DrawReason::Agreement))
+    }
+
+    /// Claims a draw by threefold repetition or the fifty-move rule, if either applies.
+    pub fn claim_draw(&mut self) -> Result<(), ChessError> {
+        if self.status().is_over() {
+            return Err(ChessError::GameOver);
+        }
+        let reason = if self.is_threefold_repetition() {
+            DrawReason::ThreefoldRepetition
+        } else if self.halfmove_clock >= 100 {
+            DrawReason::FiftyMoveRule
+        } else {
+            return Err(ChessError::NoDrawToClaim);
+        };
+        self.termination = Some(GameStatus::Draw(reason));
+        Ok(())
+    }
+}
+
+impl ChessGame {
+    // A result already reached, on the board or off it, stands
+    fn end_game(&mut self, status: GameStatus) -> Result<(), ChessError> {
+        if self.status().is_over() {
+            return Err(ChessError::GameOver);
+        }
+        self.termination = Some(status);
+        Ok(())
+    }
+}
+
+impl ChessGame {
+    /// Works out the game status. Resignation, timeout and agreed or claimed draws come
+    /// from the calls that recorded them; everything else is read off the position.
+    /// Claimable draws (threefold repetition, fifty moves) only end the game once claimed.
+    pub fn status(&self) -> GameStatus {
+        if let Some(termination) = self.termination {
+            return termination;
+        }
+
+        let in_check = self.in_check(self.current_turn);
+        if self.legal_moves().is_empty() {
+            return if in_check {
+                GameStatus::Checkmate { winner: self.current_turn.opponent() }
+            } else {
+                GameStatus::Stalemate
+            };
+        }
+        if self.is_insufficient_material() {
+            return GameStatus::Draw(DrawReason::InsufficientMaterial);
+        }
+        if self.is_dead_position() {
+            return GameStatus::Draw(DrawReason::DeadPosition);
+        }
+        if self.is_fivefold_repetition() {
+            return GameStatus::Draw(DrawReason::FivefoldRepetition);
+        }
+        if self.halfmove_clock >= 150 {
+            return GameStatus::Draw(DrawReason::SeventyFiveMoveRule);
+        }
+
+        if in_check { GameStatus::Check } else { GameStatus::Ongoing }
+    }
+}
@@ -0,0 +1,295 @@
+#[cfg(feature = "wasm")]
+use wasm_bindgen::prelude::*;
+
+use crate::annotation::Annotation;
+use crate::error::ChessError;
+use crate::movegen::Move;
+use crate::pgn::{annotated_move_tokens, parse_tags_json, write_pgn, PgnError, PgnGame};
+use crate::ChessGame;
+
+/// A move in a `GameTree` and the position it leads to.
+#[derive(Debug, Clone)]
+pub struct Node {
+    parent: Option<usize>,
+    mv: Option<Move>,
+    game: ChessGame,
+    // The first child continues the line, the rest are its variations
+    children: Vec<usize>,
+    annotation: Annotation,
+}
+
+impl Node {
+    /// The node this move was played from; `None` for the root.
+    pub fn parent(&self) -> Option<usize> {
+        self.parent
+    }
+
+    /// The move leading here; `None` for the root.
+    pub fn mv(&self) -> Option<Move> {
+        self.mv
+    }
+
+    /// The position after the move, with the moves leading to it as its history.
+    pub fn game(&self) -> &ChessGame {
+        &self.game
+    }
+
+    pub fn children(&self) -> &[usize] {
+        &self.children
+    }
+
+    /// Comments, NAGs, clock and evaluation on the move. On the root, a comment
+    /// about the game as a whole.
+    pub fn annotation(&self) -> &Annotation {
+        &self.annotation
+    }
+}
+
+/// A game with variations, for analysis. Nodes are named by ids that stay valid until
+/// the node is deleted; the root, the starting position, is always 0. One node is
+/// selected at a time and `game` is the position there.
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+#[derive(Debug, Clone)]
+pub struct GameTree {
+    // Deleted nodes leave a hole so the other ids stay put
+    nodes: Vec<Option<Node>>,
+    current: usize,
+}
+
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+impl GameTree {
+    /// A tree rooted at the position of `game`.
+    #[cfg_attr(feature = "wasm", wasm_bindgen(constructor))]
+    pub fn new(mut game: ChessGame) -> GameTree {
+        game.pending_promotion = None;
+        GameTree { nodes: vec![Some(Node { parent: None, mv: None, game, children: Vec::new(), annotation: Annotation::default() })], current: 0 }
+    }
+
+    /// The id of the selected node.
+    pub fn current(&self) -> usize {
+        self.current
+    }
+
+    /// A copy of the position at the selected node.
+    pub fn current_game(&self) -> ChessGame {
+        self.game().clone()
+    }
+
+    pub fn fen(&self) -> Result<String, ChessError> {
+        self.game().fen()
+    }
+
+    pub fn children_of(&self, id: usize) -> Vec<usize> {
+        self.node(id).map_or_else(Vec::new, |node| node.children.clone())
+    }
+
+    pub fn parent_of(&self, id: usize) -> Option<usize> {
+        self.node(id).and_then(|node| node.parent)
+    }
+
+    /// The SAN of the move leading to `id`.
+    pub fn san_of(&self, id: usize) -> Option<String> {
+        let node = self.node(id)?;
+        let parent = self.node(node.parent?)?;
+        Some(parent.game.san(&node.mv?))
+    }
+
+    /// Plays a SAN move from the selected node and selects the result. A move that is
+    /// already there is followed instead of added; otherwise it starts a new variation,
+    /// or continues the line if the node had no moves after it.
+    pub fn play_san(&mut self, san: &str) -> Result<usize, ChessError> {
+        let mv = self.game().parse_san(san)?;
+        Ok(self.add_move(self.current, mv))
+    }
+
+    /// Plays a UCI move from the selected node, see `play_san`.
+    pub fn play_uci(&mut self, uci: &str) -> Result<usize, ChessError> {
+        let mv = self.game().parse_uci(uci)?;
+        Ok(self.add_move(self.current, mv))
+    }
+
+    /// Selects the node `id`. Returns false if there is no such node.
+    pub fn goto(&mut self, id: usize) -> bool {
+        if self.node(id).is_none() {
+            return false;
+        }
+        self.current = id;
+        true
+    }
+
+    /// Selects the parent of the selected node. Returns false at the root.
+    pub fn back(&mut self) -> bool {
+        match self.parent_of(self.current) {
+            Some(parent) => self.goto(parent),
+            None => false,
+        }
+    }
+
+    /// Selects the next move in the line. Returns false at the end of it.
+    pub fn forward(&mut self) -> bool {
+        match self.children_of(self.current).first() {
+            Some(&next) => self.goto(next),
+            None => false,
+        }
+    }
+
+    /// Makes the line through `id` the main line, at its own branch point and every
+    /// one above it. Returns false for the root or a missing node.
+    pub fn promote_variation(&mut self, id: usize) -> bool {
+        if self.parent_of(id).is_none() {
+            return false;
+        }
+        let mut child = id;
+        while let Some(parent) = self.parent_of(child) {
+            let children = &mut self.nodes[parent].as_mut().expect("parents outlive their children").children;
+            let index = children.iter().position(|&c| c == child).expect("a node is among its parent's children");
+            let promoted = children.remove(index);
+            children.insert(0, promoted);
+            child = parent;
+        }
+        true
+    }
+
+    /// Removes `id` and every move after it. The selection moves up to the parent if it
+    /// was among them. Returns false for the root or a missing node.
+    pub fn delete_variation(&mut self, id: usize) -> bool {
+        let parent = match self.parent_of(id) {
+            Some(parent) => parent,
+            None => return false,
+        };
+        if self.path_to(self.current).contains(&id) {
+            self.current = parent;
+        }
+        self.nodes[parent].as_mut().expect("parents outlive their children").children.retain(|&c| c != id);
+        let mut doomed = vec![id];
+        while let Some(next) = doomed.pop() {
+            if let Some(node) = self.nodes[next].take() {
+                doomed.extend(node.children);
+            }
+        }
+        true
+    }
+
+    /// The whole tree as PGN, variations in parentheses, see `ChessGame::pgn`.
+    /// `tags_json` is a JSON object of tag names to values.
+    pub fn to_pgn(&self, tags_json: &str) -> Result<String, ChessError> {
+        let tags = parse_tags_json(tags_json)?;
+        let pairs: Vec<(&str, &str)> = tags.iter().map(|(name, value)| (name.as_str(), value.as_str())).collect();
+        Ok(self.pgn(&pairs))
+    }
+}
+
+impl GameTree {
+    /// The first game of a PGN text with all its variations.
+    pub fn from_pgn(pgn: &str) -> Result<GameTree, PgnError> {
+        Ok(PgnGame::read_first(pgn)?.tree)
+    }
+
+    pub fn node(&self, id: usize) -> Option<&Node> {
+        self.nodes.get(id).and_then(Option::as_ref)
+    }
+
+    pub fn annotation_mut(&mut self, id: usize) -> Option<&mut Annotation> {
+        self.nodes.get_mut(id).and_then(Option::as_mut).map(|node| &mut node.annotation)
+    }
+
+    /// The position at the selected node.
+    pub fn game(&self) -> &ChessGame {
+        &self.node(self.current).expect("the selected node exists").game
+    }
+
+    /// Adds `mv`, which must be legal, after `parent` and selects it. If the move is
+    /// already there, that node is selected instead.
+    ///
+    /// Panics if `parent` doesn't exist.
+    pub fn add_move(&mut self, parent: usize, mv: Move) -> usize {
+        let node = self.node(parent).expect("add_move: no such node");
+        if let Some(&existing) = node.children.iter().find(|&&c| self.nodes[c].as_ref().and_then(|n| n.mv) == Some(mv)) {
+            self.current = existing;
+            return existing;
+        }
+        let mut game = node.game.clone();
+        game.make(mv.from, mv.to, mv.promotion);
+
+        let id = self.nodes.len();
+        self.nodes.push(Some(Node { parent: Some(parent), mv: Some(mv), game, children: Vec::new(), annotation: Annotation::default() }));
+        self.nodes[parent].as_mut().expect("checked above").children.push(id);
+        self.current = id;
+        id
+    }
+
+    /// The main line from the root: the root followed by every first child.
+    pub fn mainline(&self) -> Vec<usize> {
+        let mut line = vec![0];
+        while let Some(&next) = self.children_of(*line.last().expect("starts with the root")).first() {
+            line.push(next);
+        }
+        line
+    }
+
+    /// The nodes from the root down to `id`, both included.
+    pub fn path_to(&self, id: usize) -> Vec<usize> {
+        let mut path = Vec::new();
+        let mut next = self.node(id).map(|_| id);
+        while let Some(id) = next {
+            path.push(id);
+            next = self.parent_of(id);
+        }
+        path.reverse();
+        path
+    }
+
+    /// The tree as PGN. The result is that of the position at the end of the main line,
+    /// or the one read from PGN there when the position doesn't end the game.
+    pub fn pgn(&self, tags: &[(&str, &str)]) -> String {
+        let end = *self.mainline().last().expect("starts with the root");
+        let result = self.node(end).expect("on the main line").game.pgn_result();
+        let mut movetext: Vec<String> = self.node(0).and_then(|root| root.annotation.pgn_comment()).into_iter().collect();
+        self.line_movetext(0, true, &mut movetext);
+        write_pgn(&self.node(0).expect("the root is never deleted").game, result, tags, movetext)
+    }
+
+    /// Keeps a PGN result at the end of the main line, until a move is played from there.
+    /// Anything but "1-0", "0-1" and "1/2-1/2" is ignored.
+    pub(crate) fn record_result(&mut self, result: &str) {
+        let recorded = match result {
+            "1-0" => "1-0",
+            "0-1" => "0-1",
+            "1/2-1/2" => "1/2-1/2",
+            _ => return,
+        };
+        let end = *self.mainline().last().expect("starts with the root");
+        self.nodes[end].as_mut().expect("on the main line").game.recorded_result = Some(recorded);
+    }
+
+    /// The moves after `id`, each followed by the variations branching off before it.
+    /// `numbered` asks for a move number even on a move by Black.
+    fn line_movetext(&self, mut id: usize, mut numbered: bool, movetext: &mut Vec<String>) {
+        loop {
+            let node = self.node(id).expect("children exist");
+            let (main, variations) = match node.children.split_first() {
+                Some(split) => split,
+                None => return,
+            };
+            // One copy of the position serves the move and all its alternatives
+            let mut position = node.game.clone();
+            let main_commented = self.move_movetext(&mut position, *main, numbered, movetext);
+            for &variation in variations {
+                movetext.push("(".to_string());
+                let commented = self.move_movetext(&mut position, variation, true, movetext);
+                self.line_movetext(variation, commented, movetext);
+                movetext.push(")".to_string());
+            }
+            // After a comment or a variation the line picks up again with its move number
+            numbered = main_commented || !variations.is_empty();
+            id = *main;
+        }
+    }
+
+    /// Writes the move at `id`, played in `position`, with its NAGs and comment.
+    /// Returns whether there was a comment.
+    fn move_movetext(&self, position: &mut ChessGame, id: usize, numbered: bool, movetext: &mut Vec<String>) -> bool {
+        let node = self.node(id).expect("children exist");
+        annotated_move_tokens(position, &node.mv.expect("only the root has no move"), numbered, &node.annotation, movetext)
+    }
+}
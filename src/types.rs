@@ -0,0 +1,247 @@
+use std::fmt;
+
+use serde::ser::{Serialize, SerializeTuple, Serializer};
+
+use crate::constants::{
+    W_PAWN, W_ROOK, W_KNIGHT, W_BISHOP, W_QUEEN, W_KING,
+    B_PAWN, B_ROOK, B_KNIGHT, B_BISHOP, B_QUEEN, B_KING,
+    WHITE, BLACK,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Color {
+    White,
+    Black,
+}
+
+impl Color {
+    pub fn opponent(self) -> Color {
+        match self {
+            Color::White => Color::Black,
+            Color::Black => Color::White,
+        }
+    }
+
+    pub(crate) fn index(self) -> usize {
+        self as usize
+    }
+
+    /// The i32 encoding used on the JS side: 1 for White, 2 for Black.
+    pub fn code(self) -> i32 {
+        match self {
+            Color::White => WHITE,
+            Color::Black => BLACK,
+        }
+    }
+
+    pub fn from_code(code: i32) -> Option<Color> {
+        match code {
+            WHITE => Some(Color::White),
+            BLACK => Some(Color::Black),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for Color {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Color::White => "White",
+            Color::Black => "Black",
+        })
+    }
+}
+
+// Declared in the order of the i32 piece codes
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PieceKind {
+    Pawn,
+    Rook,
+    Knight,
+    Bishop,
+    Queen,
+    King,
+}
+
+impl PieceKind {
+    pub const ALL: [PieceKind; 6] = [
+        PieceKind::Pawn, PieceKind::Rook, PieceKind::Knight,
+        PieceKind::Bishop, PieceKind::Queen, PieceKind::King,
+    ];
+
+    /// The pieces a pawn may promote to, queen first.
+    pub const PROMOTIONS: [PieceKind; 4] = [PieceKind::Queen, PieceKind::Rook, PieceKind::Bishop, PieceKind::Knight];
+
+    pub(crate) fn index(self) -> usize {
+        self as usize
+    }
+
+    /// Uppercase letter as used in FEN and SAN.
+    pub fn letter(self) -> char {
+        match self {
+            PieceKind::Pawn => 'P',
+            PieceKind::Rook => 'R',
+            PieceKind::Knight => 'N',
+            PieceKind::Bishop => 'B',
+            PieceKind::Queen => 'Q',
+            PieceKind::King => 'K',
+        }
+    }
+
+    /// Accepts either case.
+    pub fn from_letter(letter: char) -> Option<PieceKind> {
+        match letter.to_ascii_uppercase() {
+            'P' => Some(PieceKind::Pawn),
+            'R' => Some(PieceKind::Rook),
+            'N' => Some(PieceKind::Knight),
+            'B' => Some(PieceKind::Bishop),
+            'Q' => Some(PieceKind::Queen),
+            'K' => Some(PieceKind::King),
+            _ => None,
+        }
+    }
+}
+
+// Serialized as the white piece code (1-6), which doubles as the piece type on the JS side
+impl Serialize for PieceKind {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_i32(self.index() as i32 + 1)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Piece {
+    pub color: Color,
+    pub kind: PieceKind,
+}
+
+impl Piece {
+    pub fn new(color: Color, kind: PieceKind) -> Piece {
+        Piece { color, kind }
+    }
+
+    /// The i32 encoding from `constants`: 1-6 for White, 7-12 for Black.
+    pub fn code(self) -> i32 {
+        match (self.color, self.kind) {
+            (Color::White, PieceKind::Pawn) => W_PAWN,
+            (Color::White, PieceKind::Rook) => W_ROOK,
+            (Color::White, PieceKind::Knight) => W_KNIGHT,
+            (Color::White, PieceKind::Bishop) => W_BISHOP,
+            (Color::White, PieceKind::Queen) => W_QUEEN,
+            (Color::White, PieceKind::King) => W_KING,
+            (Color::Black, PieceKind::Pawn) => B_PAWN,
+            (Color::Black, PieceKind::Rook) => B_ROOK,
+            (Color::Black, PieceKind::Knight) => B_KNIGHT,
+            (Color::Black, PieceKind::Bishop) => B_BISHOP,
+            (Color::Black, PieceKind::Queen) => B_QUEEN,
+            (Color::Black, PieceKind::King) => B_KING,
+        }
+    }
+
+    pub fn from_code(code: i32) -> Option<Piece> {
+        let color = match code {
+            1..=6 => Color::White,
+            7..=12 => Color::Black,
+            _ => return None,
+        };
+        Some(Piece::new(color, PieceKind::ALL[((code - 1) % 6) as usize]))
+    }
+
+    /// Same as `code`, but 0 for an empty square.
+    pub fn code_of(piece: Option<Piece>) -> i32 {
+        piece.map_or(0, Piece::code)
+    }
+
+    /// FEN letter: uppercase for White, lowercase for Black.
+    pub fn fen_char(self) -> char {
+        match self.color {
+            Color::White => self.kind.letter(),
+            Color::Black => self.kind.letter().to_ascii_lowercase(),
+        }
+    }
+
+    pub fn from_fen_char(ch: char) -> Option<Piece> {
+        let kind = PieceKind::from_letter(ch)?;
+        let color = if ch.is_ascii_uppercase() { Color::White } else { Color::Black };
+        Some(Piece::new(color, kind))
+    }
+}
+
+// Serialized as its i32 code, matching `get_board`
+impl Serialize for Piece {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_i32(self.code())
+    }
+}
+
+/// A board square. Like the (x, y) coordinates of the JS API, x counts ranks from
+/// the top (0 is rank 8) and y counts files from the left (0 is the a-file).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Square(u8);
+
+impl Square {
+    /// Returns `None` when the coordinates are off the board.
+    pub fn new(x: usize, y: usize) -> Option<Square> {
+        if x < 8 && y < 8 {
+            Some(Square((x * 8 + y) as u8))
+        } else {
+            None
+        }
+    }
+
+    pub(crate) fn from_index(index: usize) -> Square {
+        debug_assert!(index < 64);
+        Square(index as u8)
+    }
+
+    pub(crate) fn index(self) -> usize {
+        self.0 as usize
+    }
+
+    pub fn x(self) -> usize {
+        self.index() / 8
+    }
+
+    pub fn y(self) -> usize {
+        self.index() % 8
+    }
+
+    /// Parses a square like "e4".
+    pub fn from_algebraic(name: &str) -> Option<Square> {
+        let mut chars = name.chars();
+        match (chars.next(), chars.next(), chars.next()) {
+            (Some(file @ 'a'..='h'), Some(rank @ '1'..='8'), None) => {
+                Square::new(('8' as usize) - (rank as usize), (file as usize) - ('a' as usize))
+            }
+            _ => None,
+        }
+    }
+
+    pub fn file_char(self) -> char {
+        (b'a' + self.y() as u8) as char
+    }
+
+    pub fn rank_char(self) -> char {
+        (b'8' - self.x() as u8) as char
+    }
+
+    pub fn all() -> impl Iterator<Item = Square> {
+        (0..64).map(Square::from_index)
+    }
+}
+
+impl fmt::Display for Square {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}{}", self.file_char(), self.rank_char())
+    }
+}
+
+// Serialized as [x, y] so JSON output lines up with the coordinate-based JS API
+impl Serialize for Square {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut tuple = serializer.serialize_tuple(2)?;
+        tuple.serialize_element(&self.x())?;
+        tuple.serialize_element(&self.y())?;
+        tuple.end()
+    }
+}
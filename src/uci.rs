@@ -0,0 +1,56 @@
+#[cfg(feature = "wasm")]
+use wasm_bindgen::prelude::*;
+
+use crate::error::ChessError;
+use crate::movegen::Move;
+use crate::types::{PieceKind, Square};
+use crate::ChessGame;
+
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+impl ChessGame {
+    /// Plays a move in UCI long algebraic notation: "e2e4", "e7e8q", or "e1g1" to castle.
+    pub fn make_move_uci(&mut self, uci: &str) -> Result<(), ChessError> {
+        if self.termination.is_some() {
+            return Err(ChessError::GameOver);
+        }
+        if self.pending_promotion.is_some() {
+            return Err(ChessError::PromotionPending);
+        }
+        let mv = self.parse_uci(uci)?;
+        self.make(mv.from, mv.to, mv.promotion);
+        Ok(())
+    }
+
+    /// The last move played, in UCI notation.
+    pub fn last_move_uci(&self) -> Option<String> {
+        self.history.last().map(|undo| self.to_uci(&undo.as_move()))
+    }
+}
+
+impl ChessGame {
+    /// `mv` in UCI notation. Castling is the king's two-square move and promotions
+    /// carry the lowercase piece letter.
+    pub fn to_uci(&self, mv: &Move) -> String {
+        let mut uci = format!("{}{}", mv.from, mv.to);
+        if let Some(kind) = mv.promotion {
+            uci.push(kind.letter().to_ascii_lowercase());
+        }
+        uci
+    }
+
+    /// Resolves a UCI move string to the legal move it names.
+    pub fn parse_uci(&self, uci: &str) -> Result<Move, ChessError> {
+        let malformed = || ChessError::InvalidUci(uci.to_string());
+        if !uci.is_ascii() || !(4..=5).contains(&uci.len()) {
+            return Err(malformed());
+        }
+        let from = Square::from_algebraic(&uci[0..2]).ok_or_else(malformed)?;
+        let to = Square::from_algebraic(&uci[2..4]).ok_or_else(malformed)?;
+        let promotion = match uci[4..].chars().next() {
+            Some(letter @ ('q' | 'r' | 'b' | 'n')) => PieceKind::from_letter(letter),
+            Some(_) => return Err(malformed()),
+            None => None,
+        };
+        self.find_legal_move(from, to, promotion)
+    }
+}
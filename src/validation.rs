@@ -1,347 +1,313 @@
+use std::fmt;
+
+use serde::ser::{Serialize, SerializeStruct, Serializer};
+#[cfg(feature = "wasm")]
 use wasm_bindgen::prelude::*;
 
-use crate::constants::{
-    W_KING, B_KING,
-    WHITE, BLACK,
-};
+use crate::bitboard::{bit, EMPTY};
+use crate::chess960::{castled_squares, castling_index};
+use crate::types::{Color, Piece, PieceKind, Square};
 use crate::utils;
 use crate::ChessGame;
 
-#[wasm_bindgen]
+/// Why `explain_move` rejected a move.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IllegalMoveReason {
+    OutOfBounds,
+    EmptyStartSquare,
+    WrongTurn,
+    OwnPieceOnTarget,
+    /// The piece can't move that way, whatever stands in between.
+    WrongShape(PieceKind),
+    /// A piece on this square stands between the start and the target.
+    PathBlocked(Square),
+    LeavesKingInCheck,
+    /// The king would start on, pass or land on this attacked square.
+    CastlingThroughAttackedSquare(Square),
+    CastlingRightsLost,
+    /// A pawn moving straight ahead runs into the piece on this square.
+    PawnBlocked(Square),
+}
+
+impl IllegalMoveReason {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            IllegalMoveReason::OutOfBounds => "out_of_bounds",
+            IllegalMoveReason::EmptyStartSquare => "empty_start_square",
+            IllegalMoveReason::WrongTurn => "wrong_turn",
+            IllegalMoveReason::OwnPieceOnTarget => "own_piece_on_target",
+            IllegalMoveReason::WrongShape(_) => "wrong_shape",
+            IllegalMoveReason::PathBlocked(_) => "path_blocked",
+            IllegalMoveReason::LeavesKingInCheck => "leaves_king_in_check",
+            IllegalMoveReason::CastlingThroughAttackedSquare(_) => "castling_through_attacked_square",
+            IllegalMoveReason::CastlingRightsLost => "castling_rights_lost",
+            IllegalMoveReason::PawnBlocked(_) => "pawn_blocked",
+        }
+    }
+
+    /// The square the reason refers to, if any.
+    pub fn square(&self) -> Option<Square> {
+        match self {
+            IllegalMoveReason::PathBlocked(sq)
+            | IllegalMoveReason::CastlingThroughAttackedSquare(sq)
+            | IllegalMoveReason::PawnBlocked(sq) => Some(*sq),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for IllegalMoveReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            IllegalMoveReason::OutOfBounds => f.write_str("the move leaves the board"),
+            IllegalMoveReason::EmptyStartSquare => f.write_str("there is no piece on the starting square"),
+            IllegalMoveReason::WrongTurn => f.write_str("that piece belongs to the side not on move"),
+            IllegalMoveReason::OwnPieceOnTarget => f.write_str("a piece of the same color stands on the target square"),
+            IllegalMoveReason::WrongShape(kind) => write!(f, "a {} can't move like that", format!("{:?}", kind).to_lowercase()),
+            IllegalMoveReason::PathBlocked(sq) => write!(f, "the path is blocked at {}", sq),
+            IllegalMoveReason::LeavesKingInCheck => f.write_str("the move would leave the king in check"),
+            IllegalMoveReason::CastlingThroughAttackedSquare(sq) => write!(f, "the king can't castle through the attacked square {}", sq),
+            IllegalMoveReason::CastlingRightsLost => f.write_str("castling on that side is no longer allowed"),
+            IllegalMoveReason::PawnBlocked(sq) => write!(f, "the pawn is blocked by the piece on {}", sq),
+        }
+    }
+}
+
+// Serialized as {"reason": "path_blocked", "square": [4, 4], "message": "..."}
+impl Serialize for IllegalMoveReason {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut state = serializer.serialize_struct("IllegalMoveReason", 3)?;
+        state.serialize_field("reason", self.as_str())?;
+        state.serialize_field("square", &self.square())?;
+        state.serialize_field("message", &self.to_string())?;
+        state.end()
+    }
+}
+
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
 impl ChessGame {
     pub fn is_valid_move(&self, start_x: i32, start_y: i32, end_x: i32, end_y: i32) -> bool {
-        utils::log(&format!("Checking move from ({},{}) to ({},{})", start_x, start_y, end_x, end_y));
-        
-        // 1. Basic Bounds & Same Square
-        if !Self::is_on_board(start_x, start_y) || !Self::is_on_board(end_x, end_y) { 
-            utils::log("Move failed: out of bounds");
-            return false; 
+        self.explain_move_at(start_x, start_y, end_x, end_y).is_ok()
+    }
+
+    /// Why the move from (start_x, start_y) to (end_x, end_y) is illegal, as JSON:
+    /// `null` for a legal move, otherwise `{"reason": ..., "square": ..., "message": ...}`.
+    pub fn explain_move_json(&self, start_x: i32, start_y: i32, end_x: i32, end_y: i32) -> String {
+        match serde_json::to_string(&self.explain_move_at(start_x, start_y, end_x, end_y).err()) {
+            Ok(json) => json,
+            Err(e) => format!("{{\"error\": \"{}\"}}", e),
         }
-        if start_x == end_x && start_y == end_y { 
-            utils::log("Move failed: same square");
-            return false; 
+    }
+
+    /// Checks if the king of the specified color is currently in check.
+    /// color: 1 for White, 2 for Black
+    pub fn is_check(&self, color: i32) -> bool {
+        match Color::from_code(color) {
+            Some(color) => self.in_check(color),
+            None => false,
         }
+    }
+}
 
-        // 2. Get Piece Info & Check Turn
-        let piece = self.get_piece(start_x as usize, start_y as usize);
-        if piece == 0 { 
-            utils::log("Move failed: no piece at start");
-            return false; 
+impl ChessGame {
+    /// Checks a move for the side to move and says exactly what is wrong with it.
+    pub fn explain_move(&self, from: Square, to: Square) -> Result<(), IllegalMoveReason> {
+        // 1. Get Piece Info & Check Turn
+        let piece = self.piece_at(from).ok_or(IllegalMoveReason::EmptyStartSquare)?;
+        if piece.color != self.current_turn {
+            return Err(IllegalMoveReason::WrongTurn);
         }
-        
-        let piece_color = utils::get_piece_color(piece);
-        if piece_color != self.current_turn { 
-            utils::log(&format!("Move failed: wrong turn. Piece color: {}, Current turn: {}", piece_color, self.current_turn));
-            return false; 
+
+        // 2. Check Target Square (Capture Rule), though a Chess960 king castles onto its own rook
+        let castling = piece.kind == PieceKind::King && self.castling_rook(from, to, piece.color).is_some();
+        if self.piece_at(to).map(|target| target.color) == Some(piece.color) && !castling {
+            return Err(IllegalMoveReason::OwnPieceOnTarget);
         }
 
-        let piece_type = utils::get_piece_type(piece);
-        utils::log(&format!("Moving piece type: {}, color: {}", piece_type, piece_color));
-
-        // 3. Get Destination Info
-        let ending_piece = self.get_piece(end_x as usize, end_y as usize);
-        let ending_piece_color = utils::get_piece_color(ending_piece);
-
-        // 4. Check Target Square (Capture Rule)
-        if ending_piece != 0 && ending_piece_color == piece_color { return false; } // Cannot capture friendly piece
-
-        // 5. Validate Move Shape, Obstructions, and Special Moves
-        let is_basic_move_valid = match piece_type {
-            1 => self.is_valid_pawn_move_detailed(start_x, start_y, end_x, end_y, piece_color, ending_piece, ending_piece_color),
-            2 => {
-                utils::is_valid_rook_move(start_x, start_y, end_x, end_y) &&
-                self.is_path_clear(start_x, start_y, end_x, end_y)
-            },
-            3 => utils::is_valid_knight_move(start_x, start_y, end_x, end_y),
-            4 => {
-                utils::is_valid_bishop_move(start_x, start_y, end_x, end_y) &&
-                self.is_path_clear(start_x, start_y, end_x, end_y)
-            },
-            5 => {
-                utils::is_valid_queen_move(start_x, start_y, end_x, end_y) &&
-                self.is_path_clear(start_x, start_y, end_x, end_y)
-            },
-            6 => self.is_valid_king_move_detailed(start_x, start_y, end_x, end_y, piece_color),
-            _ => false // Unknown piece type just in case?
+        // 3. Validate Move Shape, Obstructions, and Special Moves
+        let (start_x, start_y, end_x, end_y) = (from.x() as i32, from.y() as i32, to.x() as i32, to.y() as i32);
+        let shape_ok = match piece.kind {
+            PieceKind::Pawn => return self.check_pawn_move(from, to, piece.color).and_then(|()| self.check_king_safety(from, to)),
+            PieceKind::King => return self.check_king_move(from, to, piece.color).and_then(|()| self.check_king_safety(from, to)),
+            PieceKind::Rook => utils::is_valid_rook_move(start_x, start_y, end_x, end_y),
+            PieceKind::Knight => utils::is_valid_knight_move(start_x, start_y, end_x, end_y),
+            PieceKind::Bishop => utils::is_valid_bishop_move(start_x, start_y, end_x, end_y),
+            PieceKind::Queen => utils::is_valid_queen_move(start_x, start_y, end_x, end_y),
         };
-        // If the basic shape/rules aren't valid, the move is impossible.
-        if !is_basic_move_valid {
-            return false;
+        if !shape_ok {
+            return Err(IllegalMoveReason::WrongShape(piece.kind));
+        }
+        if piece.kind != PieceKind::Knight {
+            if let Some(blocker) = self.first_blocker(from, to) {
+                return Err(IllegalMoveReason::PathBlocked(blocker));
+            }
         }
 
-        // 6. Check if Move Puts Own King in Check (Simulation)
-        let mut temp_game = self.clone();
+        // 4. Check if Move Puts Own King in Check
+        self.check_king_safety(from, to)
+    }
+
+    // `explain_move` for the coordinates the wasm API takes, which may leave the board
+    fn explain_move_at(&self, start_x: i32, start_y: i32, end_x: i32, end_y: i32) -> Result<(), IllegalMoveReason> {
+        if !Self::is_on_board(start_x, start_y) || !Self::is_on_board(end_x, end_y) {
+            return Err(IllegalMoveReason::OutOfBounds);
+        }
+        self.explain_move(Square::new(start_x as usize, start_y as usize).unwrap(), Square::new(end_x as usize, end_y as usize).unwrap())
+    }
 
-        //  simulate the move, It won't update state or turn.
-        if let Err(_) = temp_game.make_move_internal(start_x, start_y, end_x, end_y, true) {
-            // this should ideally not happen if is_basic_move_valid is true, but just to be sure
-            return false; 
+    fn check_king_safety(&self, from: Square, to: Square) -> Result<(), IllegalMoveReason> {
+        if self.leaves_king_in_check(from, to) {
+            Err(IllegalMoveReason::LeavesKingInCheck)
+        } else {
+            Ok(())
         }
+    }
 
-        if temp_game.is_check(piece_color) {
-            utils::log("Move failed: leaves king in check");
-            return false; // Move is invalid, the king in check
+    /// Checks whether moving the piece at start to end would leave its own king attacked.
+    /// The resulting occupancy is worked out on the bitboards, so nothing is copied or mutated.
+    pub(crate) fn leaves_king_in_check(&self, from: Square, to: Square) -> bool {
+        let piece = match self.board.piece_at(from) {
+            Some(piece) => piece,
+            None => return false,
+        };
+        // Castling checks every square the king crosses on its own
+        if piece.kind == PieceKind::King && self.castling_rook(from, to, piece.color).is_some() {
+            return false;
         }
-        true
+        let opponent_color = piece.color.opponent();
+
+        let captured = if self.is_en_passant_move(from, to) {
+            bit(Square::new(from.x(), to.y()).unwrap().index())
+        } else {
+            bit(to.index()) & self.board.by_color(opponent_color)
+        };
+        let king_square = if piece.kind == PieceKind::King {
+            to
+        } else {
+            match self.board.king_square(piece.color) {
+                Some(sq) => sq,
+                None => return false,
+            }
+        };
+
+        let occupied = (self.board.occupied() & !bit(from.index()) & !captured) | bit(to.index());
+        let attackers = self.board.attackers_to(king_square, occupied) & self.board.by_color(opponent_color) & !captured;
+        attackers != EMPTY
     }
 
-    pub(crate) fn is_en_passant_move(&self, start_x: i32, start_y: i32, end_x: i32, end_y: i32) -> bool {
+    pub(crate) fn is_en_passant_move(&self, from: Square, to: Square) -> bool {
         if let Some(target_square) = self.en_passant_target {
-            let piece = self.get_piece(start_x as usize, start_y as usize);
              // Check if it's a pawn moving diagonally to the en passant target square
-             utils::get_piece_type(piece) == 1 &&
-             (end_x as usize, end_y as usize) == target_square &&
-             (start_y - end_y).abs() == 1
+             self.board.piece_at(from).map(|piece| piece.kind) == Some(PieceKind::Pawn) &&
+             to == target_square &&
+             from.y().abs_diff(to.y()) == 1
         } else {
             false
         }
     }
-    fn is_valid_pawn_move_detailed(&self, start_x: i32, start_y: i32, end_x: i32, end_y: i32, piece_color: i32, ending_piece: i32, ending_piece_color: i32) -> bool {
-        let is_white = piece_color == WHITE;
-        let dx = end_x - start_x;
-        let dy = end_y - start_y;
+
+    fn check_pawn_move(&self, from: Square, to: Square, color: Color) -> Result<(), IllegalMoveReason> {
+        let is_white = color == Color::White;
+        let dx = to.x() as i32 - from.x() as i32;
+        let dy = to.y() as i32 - from.y() as i32;
+        let expected_dx = if is_white { -1 } else { 1 };
+        let start_rank = if is_white { 6 } else { 1 };
+        let wrong_shape = Err(IllegalMoveReason::WrongShape(PieceKind::Pawn));
 
         // Standard Forward Moves
         if dy == 0 {
-            if ending_piece != 0 { 
-                utils::log("Pawn cannot move forward onto a piece");
-                return false; 
+            let is_double = dx == expected_dx * 2 && from.x() == start_rank;
+            if dx != expected_dx && !is_double {
+                return wrong_shape;
             }
-            
-            let expected_dx = if is_white { -1 } else { 1 };
-            let start_rank = if is_white { 6 } else { 1 };
-
-            utils::log(&format!("Expected dx: {}, start_rank: {}, actual start_x: {}", expected_dx, start_rank, start_x));
-
-            // Single step forward
-            if dx == expected_dx { 
-                utils::log("Valid single pawn move");
-                return true; 
-            }
-
-            // Double step forward (only from starting rank)
-            if dx == expected_dx * 2 && start_x == start_rank {
-                // Check if path is clear
-                let middle_x = start_x + expected_dx;
-                if self.get_piece(middle_x as usize, start_y as usize) != 0 {
-                    utils::log("Pawn double move blocked");
-                    return false;
+            // The square in front, then the target of a double step
+            let middle = Square::new((from.x() as i32 + expected_dx) as usize, from.y()).unwrap();
+            for sq in [middle, to] {
+                if self.piece_at(sq).is_some() {
+                    return Err(IllegalMoveReason::PawnBlocked(sq));
                 }
-                utils::log("Valid double pawn move");
-                return true;
             }
-            utils::log(&format!("Invalid pawn forward move: dx={}, expected={}, start_x={}, start_rank={}", dx, expected_dx, start_x, start_rank));
-            return false;
+            Ok(())
         }
         // Diagonal Moves (Capture or En Passant)
-        else if dy.abs() == 1 {
-            let expected_dx = if is_white { -1 } else { 1 };
-            if dx != expected_dx { 
-                utils::log("Invalid pawn diagonal direction");
-                return false; 
-            }
-
-            if ending_piece != 0 && ending_piece_color != piece_color {
-                utils::log("Valid pawn capture");
-                return true;
+        else if dy.abs() == 1 && dx == expected_dx {
+            // The target can't hold an own piece at this point
+            if self.piece_at(to).is_some() || self.is_en_passant_move(from, to) {
+                Ok(())
+            } else {
+                wrong_shape
             }
-            if ending_piece == 0 && self.is_en_passant_move(start_x, start_y, end_x, end_y) {
-                utils::log("Valid en passant");
-                return true;
-            }
-            utils::log("Invalid pawn diagonal move");
-            return false;
         }
         else {
-            utils::log("Invalid pawn move shape");
-            false
+            wrong_shape
         }
     }
 
-    fn is_valid_king_move_detailed(&self, start_x: i32, start_y: i32, end_x: i32, end_y: i32, piece_color: i32) -> bool {
-        let dx = end_x - start_x;
-        let dy = end_y - start_y;
+    pub(crate) fn check_king_move(&self, from: Square, to: Square, color: Color) -> Result<(), IllegalMoveReason> {
+        let (kingside, rook) = match self.castling_rook(from, to, color) {
+            Some(castling) => castling,
+            None if from.x().abs_diff(to.x()) <= 1 && from.y().abs_diff(to.y()) <= 1 => return Ok(()),
+            None => return Err(IllegalMoveReason::WrongShape(PieceKind::King)),
+        };
 
-        
-        if dx.abs() <= 1 && dy.abs() <= 1 {
-            return true;
+        let index = castling_index(color, kingside);
+        if !self.castling_right(index) || rook.y() != self.castling_rook_files[index] || self.piece_at(rook) != Some(Piece::new(color, PieceKind::Rook)) {
+            return Err(IllegalMoveReason::CastlingRightsLost);
         }
 
-        // Must be moving exactly 2 squares horizontally, and no vertical movement
-        if dx == 0 && dy.abs() == 2 {
-            // Cannot castle if currently in check
-            if self.is_check(piece_color) { return false; }
-
-            let opponent_color = if piece_color == WHITE { BLACK } else { WHITE };
-
-            // Kingside Castling (O-O)
-            if end_y > start_y { // e.g., e1 -> g1 (y=4 -> y=6) or e8 -> g8 (y=4 -> y=6)
-                let can_castle = if piece_color == WHITE { self.white_can_castle_kingside } else { self.black_can_castle_kingside };
-                if !can_castle { return false; }
-
-                // Check path clear between king and rook (f1/f8, g1/g8)
-                if self.get_piece(start_x as usize, (start_y + 1) as usize) != 0 || // f1/f8
-                   self.get_piece(start_x as usize, (start_y + 2) as usize) != 0    // g1/g8 (king destination)
-                { return false; }
-
-                // Check squares king passes through/lands on are not attacked
-                if self.is_square_attacked(start_x, start_y + 1, opponent_color) || // f1/f8
-                   self.is_square_attacked(start_x, start_y + 2, opponent_color)    // g1/g8
-                { return false; }
-
-                return true;
-            }
-            // Queenside Castling (O-O-O)
-            else { // e.g., e1 -> c1 (y=4 -> y=2) or e8 -> c8 (y=4 -> y=2)
-                let can_castle = if piece_color == WHITE { self.white_can_castle_queenside } else { self.black_can_castle_queenside };
-                if !can_castle { return false; }
-
-                // Check path clear between king and rook (d1/d8, c1/c8, b1/b8)
-                 // Note: b1/b8 doesn't need to be empty for castling itself, only for rook path!
-                if self.get_piece(start_x as usize, (start_y - 1) as usize) != 0 || // d1/d8
-                   self.get_piece(start_x as usize, (start_y - 2) as usize) != 0 || // c1/c8 (king destination)
-                   self.get_piece(start_x as usize, (start_y - 3) as usize) != 0    // b1/b8
-                { return false; }
-
-                // Check squares king passes through/lands on are not attacked
-                if self.is_square_attacked(start_x, start_y - 1, opponent_color) || // d1/d8
-                   self.is_square_attacked(start_x, start_y - 2, opponent_color)    // c1/c8
-                { return false; }
-
-                return true; // Valid Queenside Castle attempt
+        // Every square the king or the rook passes or lands on must be empty, apart from the two of them
+        let (king_to, rook_to) = castled_squares(from.x(), kingside);
+        for sq in rank_path(from, king_to).into_iter().chain(rank_path(rook, rook_to)) {
+            if sq != from && sq != rook && self.piece_at(sq).is_some() {
+                return Err(IllegalMoveReason::PathBlocked(sq));
             }
         }
 
-        // If not a standard move or castling, it's invalid
-        false
+        // The king may not castle out of, through or into check. Only king and rook are lifted:
+        // the rook on its new square would hide attacks along the rank.
+        let occupied = self.board.occupied() & !bit(from.index()) & !bit(rook.index());
+        for sq in std::iter::once(from).chain(rank_path(from, king_to)) {
+            if self.board.attackers_to(sq, occupied) & self.board.by_color(color.opponent()) != EMPTY {
+                return Err(IllegalMoveReason::CastlingThroughAttackedSquare(sq));
+            }
+        }
+        Ok(())
     }
 
+    /// The first occupied square strictly between `from` and `to` on a straight or diagonal line.
+    fn first_blocker(&self, from: Square, to: Square) -> Option<Square> {
+        let step_x = (to.x() as i32 - from.x() as i32).signum(); // -1, 0, or 1
+        let step_y = (to.y() as i32 - from.y() as i32).signum(); // -1, 0, or 1
 
-    /// Helper function to check if the path is clear for Rook, Bishop, Queen moves.
-    fn is_path_clear(&self, start_x: i32, start_y: i32, end_x: i32, end_y: i32) -> bool {
-        let dx = end_x - start_x;
-        let dy = end_y - start_y;
-
-        let step_x = dx.signum(); // -1, 0, or 1
-        let step_y = dy.signum(); // -1, 0, or 1
-
-        let mut current_x = start_x + step_x;
-        let mut current_y = start_y + step_y;
+        let mut current_x = from.x() as i32 + step_x;
+        let mut current_y = from.y() as i32 + step_y;
 
         // Iterate until one step before the end square
-        while current_x != end_x || current_y != end_y {
-            if !Self::is_on_board(current_x, current_y) {
-                // This shouldn't happen if start/end are on board and move shape is valid, but safety check
-                return false;
-            }
-            if self.get_piece(current_x as usize, current_y as usize) != 0 {
-                return false; // Path is blocked
+        while (current_x, current_y) != (to.x() as i32, to.y() as i32) {
+            let sq = Square::new(current_x as usize, current_y as usize)?;
+            if self.piece_at(sq).is_some() {
+                return Some(sq);
             }
             current_x += step_x;
             current_y += step_y;
         }
-
-        true // Path is clear
+        None
     }
 
-    /// Checks if the king of the specified color is currently in check.
-    /// color: 1 for White, 2 for Black
-    pub fn is_check(&self, color: i32) -> bool {
-        // 1. Find the King
-        let king_piece = if color == WHITE { W_KING } else { B_KING };
-        let mut king_x = -1;
-        let mut king_y = -1;
-
-        for r in 0..8 {
-            for c in 0..8 {
-                if self.board[r][c] == king_piece {
-                    king_x = r as i32;
-                    king_y = c as i32;
-                    break;
-                }
-            }
-            if king_x != -1 { break; }
-        }
-
-        if king_x == -1 {
-             utils::log(&format!("King for color {} not found!", color));
-            return false; // Improve error handling?
-        }
+    /// Whether the king of `color` is attacked.
+    pub fn in_check(&self, color: Color) -> bool {
+        // A position being set up may lack a king, see `validate_position`
+        let king_square = match self.board.king_square(color) {
+            Some(sq) => sq,
+            None => return false,
+        };
 
-        // 2. Check if the king's square is attacked by the opponent
-        let opponent_color = if color == WHITE { BLACK } else { WHITE };
-        self.is_square_attacked(king_x, king_y, opponent_color)
+        self.board.is_square_attacked(king_square, color.opponent())
     }
-    fn is_square_attacked(&self, target_x: i32, target_y: i32, attacker_color: i32) -> bool {
-        if !Self::is_on_board(target_x, target_y) {
-            return false; // theoricallyshould not happen if called correctly
-        }
-
-        for r in 0..8 {
-            for c in 0..8 {
-                let piece = self.board[r][c];
-                if piece != 0 && utils::get_piece_color(piece) == attacker_color {
-                    // Check if this opponent piece can attack the target square
-                    if self.is_valid_move_shape_and_obstruction(r as i32, c as i32, target_x, target_y) {
-                        return true;
-                    }
-                }
-            }
-        }
-        false
-    }
-
-    fn is_valid_move_shape_and_obstruction(&self, start_x: i32, start_y: i32, end_x: i32, end_y: i32) -> bool {
-
-        // 1. Bounds and Same Square (already checked by caller usually, but safe)
-        if !Self::is_on_board(start_x, start_y) || !Self::is_on_board(end_x, end_y) { return false; }
-        if start_x == end_x && start_y == end_y { return false; }
-
-        let piece = self.get_piece(start_x as usize, start_y as usize);
-        if piece == 0 { return false; }
-        let piece_color = utils::get_piece_color(piece);
-        let piece_type = utils::get_piece_type(piece);
-
-        let ending_piece = self.get_piece(end_x as usize, end_y as usize);
-        let ending_piece_color = utils::get_piece_color(ending_piece);
-
-        // (Cannot capture friendly piece)
-        if ending_piece != 0 && ending_piece_color == piece_color { return false; }
-
-        match piece_type {
-            1 => { // Pawn
-                let is_white_attacker = piece_color == WHITE;
-                let dx = end_x - start_x;
-                let dy = end_y - start_y;
+}
 
-                if dy.abs() == 1 { // Diagonal Capture
-                    let expected_dx_for_attack = if is_white_attacker {-1} else {1}; //must be 1 square forward
-                    return dx == expected_dx_for_attack;
-                }
-                //If not a one-step diagonal move, the pawn is not attacking this square.
-                return false;
-            },
-            2 => {
-                if !utils::is_valid_rook_move(start_x, start_y, end_x, end_y) { return false; }
-                self.is_path_clear(start_x, start_y, end_x, end_y)
-            },
-            3 => {
-                utils::is_valid_knight_move(start_x, start_y, end_x, end_y)
-            },
-            4 => {
-                if !utils::is_valid_bishop_move(start_x, start_y, end_x, end_y) { return false; }
-                 self.is_path_clear(start_x, start_y, end_x, end_y)
-            },
-            5 => {
-                if !utils::is_valid_queen_move(start_x, start_y, end_x, end_y) { return false; }
-                 self.is_path_clear(start_x, start_y, end_x, end_y)
-            },
-            6 => {
-                // For attack checks, we only care about the one-square move shape.
-                // Castling is handled separately in the main is_valid_move.
-                utils::is_valid_king_move(start_x, start_y, end_x, end_y)
-            },
-            _ => false
-        }
-    }
+/// The squares after `from` up to and including `to`, both on the same rank.
+fn rank_path(from: Square, to: Square) -> Vec<Square> {
+    let files: Vec<usize> = if to.y() > from.y() { (from.y() + 1..=to.y()).collect() } else { (to.y()..from.y()).rev().collect() };
+    files.into_iter().map(|y| Square::new(from.x(), y).unwrap()).collect()
 }
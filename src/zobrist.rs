@@ -0,0 +1,97 @@
+// Zobrist keys for hashing positions. The keys are generated at compile time
+// from a fixed seed, so hashes are stable across runs and builds.
+
+use crate::bitboard::{squares, PAWN_ATTACKS};
+use crate::types::{Color, PieceKind, Square};
+use crate::ChessGame;
+
+const fn splitmix64(state: u64) -> (u64, u64) {
+    let state = state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+    let mut z = state;
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    (state, z ^ (z >> 31))
+}
+
+pub struct Keys {
+    // Indexed by [piece code - 1][square]
+    pub pieces: [[u64; 64]; 12],
+    pub black_to_move: u64,
+    // White kingside, white queenside, black kingside, black queenside
+    pub castling: [u64; 4],
+    pub en_passant_file: [u64; 8],
+}
+
+const fn generate() -> Keys {
+    let mut keys = Keys { pieces: [[0; 64]; 12], black_to_move: 0, castling: [0; 4], en_passant_file: [0; 8] };
+    let mut state = 0x5EED_C4E5_5000_0001;
+    let mut piece = 0;
+    while piece < 12 {
+        let mut sq = 0;
+        while sq < 64 {
+            let (next, key) = splitmix64(state);
+            state = next;
+            keys.pieces[piece][sq] = key;
+            sq += 1;
+        }
+        piece += 1;
+    }
+    let (next, key) = splitmix64(state);
+    state = next;
+    keys.black_to_move = key;
+    let mut i = 0;
+    while i < 4 {
+        let (next, key) = splitmix64(state);
+        state = next;
+        keys.castling[i] = key;
+        i += 1;
+    }
+    let mut file = 0;
+    while file < 8 {
+        let (next, key) = splitmix64(state);
+        state = next;
+        keys.en_passant_file[file] = key;
+        file += 1;
+    }
+    keys
+}
+
+pub static KEYS: Keys = generate();
+
+impl ChessGame {
+    /// Hash of the position as it counts for repetition: piece placement, side to move,
+    /// castling rights and the en passant target when a capture onto it is actually legal.
+    pub(crate) fn position_key(&self) -> u64 {
+        let mut key = 0;
+        for sq in Square::all() {
+            if let Some(piece) = self.board.piece_at(sq) {
+                key ^= KEYS.pieces[(piece.code() - 1) as usize][sq.index()];
+            }
+        }
+        if self.current_turn == Color::Black {
+            key ^= KEYS.black_to_move;
+        }
+        let rights = [
+            self.white_can_castle_kingside,
+            self.white_can_castle_queenside,
+            self.black_can_castle_kingside,
+            self.black_can_castle_queenside,
+        ];
+        for (i, &allowed) in rights.iter().enumerate() {
+            if allowed {
+                key ^= KEYS.castling[i];
+            }
+        }
+        if let Some(target) = self.en_passant_target {
+            // Pawns of the side to move that attack the target square
+            let capturers = PAWN_ATTACKS[self.current_turn.opponent().index()][target.index()]
+                & self.board.pieces(PieceKind::Pawn, self.current_turn);
+            let can_capture = squares(capturers)
+                .any(|from| !self.leaves_king_in_check(Square::from_index(from), target));
+            if can_capture {
+                key ^= KEYS.en_passant_file[target.y()];
+            }
+        }
+        key
+    }
+}
@@ -0,0 +1,97 @@
+use std::time::Duration;
+
+use chessgame::{Annotation, ChessGame, Eval, GameTree};
+
+const ANNOTATED: &str = "{A quick game} 1. e4 $1 {[%eval 0.30] [%clk 0:05:00] King's pawn} 1... e5 \
+    2. Qh5?! {[%clk 0:04:58.5]} (2. Nf3 {[%eval #4] the usual}) 2... Nc6 3. Bc4 Nf6?? $18 \
+    {[%eval #1] [%emt 0:00:03]} 4. Qxf7# 1-0";
+
+#[test]
+fn reads_comments_nags_and_commands() {
+    let tree = GameTree::from_pgn(ANNOTATED).unwrap();
+    let line = tree.mainline();
+    let annotation = |id: usize| tree.node(id).unwrap().annotation().clone();
+
+    assert_eq!(annotation(0).comment.as_deref(), Some("A quick game"));
+    assert_eq!(
+        annotation(line[1]),
+        Annotation {
+            comment: Some("King's pawn".to_string()),
+            nags: vec![1],
+            clock: Some(Duration::from_secs(300)),
+            eval: Some(Eval::Centipawns(30)),
+        }
+    );
+    assert_eq!(annotation(line[3]).nags, [6]);
+    assert_eq!(annotation(line[3]).clock, Some(Duration::from_millis(298_500)));
+    let nf3 = tree.children_of(line[2])[1];
+    assert_eq!(annotation(nf3).eval, Some(Eval::Mate(4)));
+    // Unknown commands stay in the comment
+    assert_eq!(annotation(line[6]).nags, [4, 18]);
+    assert_eq!(annotation(line[6]).comment.as_deref(), Some("[%emt 0:00:03]"));
+    assert!(annotation(line[7]).is_empty());
+}
+
+#[test]
+fn annotations_survive_a_round_trip() {
+    let tree = GameTree::from_pgn(ANNOTATED).unwrap();
+    let pgn = tree.pgn(&[]);
+    assert!(pgn.replace('\n', " ").contains("1. e4 $1 {[%eval 0.30] [%clk 0:05:00] King's pawn} 1... e5 2. Qh5 $6"), "{}", pgn);
+    let again = GameTree::from_pgn(&pgn).unwrap();
+    assert_eq!(again.pgn(&[]), pgn);
+    for id in tree.mainline() {
+        assert_eq!(again.node(id).unwrap().annotation(), tree.node(id).unwrap().annotation());
+    }
+}
+
+#[test]
+fn a_loaded_game_keeps_its_annotations() {
+    let mut game = ChessGame::new();
+    game.load_pgn(ANNOTATED).unwrap();
+    assert_eq!(game.annotation(0).unwrap().comment.as_deref(), Some("A quick game"));
+    assert_eq!(game.annotation(1).unwrap().clock, Some(Duration::from_secs(300)));
+    let pgn = game.pgn(&[]);
+    assert!(pgn.replace('\n', " ").contains("{A quick game} 1. e4 $1 {[%eval 0.30] [%clk 0:05:00] King's pawn} 1... e5 2. Qh5 $6"), "{}", pgn);
+    let again = ChessGame::from_pgn(&pgn).unwrap();
+    assert_eq!(again.pgn(&[]), pgn);
+    for ply in 0..=game.ply_count() {
+        assert_eq!(again.annotation(ply), game.annotation(ply));
+    }
+
+    // A new move replaces the annotated one taken back
+    assert!(game.undo());
+    game.play_san("Qxf7+").unwrap();
+    assert!(game.pgn(&[]).replace('\n', " ").ends_with("{[%eval #1] [%emt 0:00:03]} 4. Qxf7# 1-0 "));
+    assert!(game.undo() && game.undo());
+    game.play_san("Nf6").unwrap();
+    assert_eq!(game.annotation(6), None);
+}
+
+#[test]
+fn editing() {
+    let mut tree = GameTree::new(ChessGame::new());
+    let e4 = tree.play_san("e4").unwrap();
+    assert!(tree.set_comment(e4, "best by test"));
+    assert!(tree.add_nag(e4, 3) && tree.add_nag(e4, 3) && tree.add_nag(e4, 14));
+    assert!(tree.remove_nag(e4, 14));
+    assert!(tree.set_clock(e4, Some(59.9)));
+    assert!(tree.set_eval(e4, "-0.5"));
+    assert!(!tree.set_eval(e4, "lots"));
+    assert_eq!(
+        tree.annotation_json(e4),
+        r#"{"comment":"best by test","nags":[3],"clock":59.9,"eval":"-0.50"}"#
+    );
+    assert!(tree.pgn(&[]).contains("1. e4 $3 {[%eval -0.50] [%clk 0:00:59.9] best by test} *"));
+
+    assert!(tree.set_clock(e4, Some(5.25)) && tree.set_comment(e4, "a {nested} remark"));
+    assert!(tree.pgn(&[]).contains("{[%eval -0.50] [%clk 0:00:05.25] a {nested) remark}"));
+    assert!(tree.set_clock(e4, Some(3600.007)));
+    assert!(tree.pgn(&[]).contains("[%clk 1:00:00.007]"));
+    assert!(!tree.set_clock(e4, Some(-1.0)) && !tree.set_clock(e4, Some(f64::NAN)));
+    assert_eq!(tree.node(e4).unwrap().annotation().clock, Some(Duration::from_millis(3_600_007)));
+
+    assert!(tree.set_comment(e4, "") && tree.set_clock(e4, None) && tree.set_eval(e4, "") && tree.remove_nag(e4, 3));
+    assert!(tree.node(e4).unwrap().annotation().is_empty());
+    assert!(!tree.set_comment(42, "nowhere"));
+    assert_eq!(tree.annotation_json(42), "null");
+}
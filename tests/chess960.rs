@@ -0,0 +1,109 @@
+use chessgame::{ChessError, ChessGame, GameStatus, GameTree};
+
+fn back_rank(sp: u32) -> String {
+    let fen = ChessGame::new_chess960(sp).unwrap().fen().unwrap();
+    fen.split(['/', ' ']).nth(7).unwrap().to_string()
+}
+
+#[test]
+fn start_positions() {
+    assert_eq!(back_rank(518), "RNBQKBNR");
+    assert_eq!(back_rank(0), "BBQNNRKR");
+    assert_eq!(back_rank(959), "RKRNNQBB");
+    assert_eq!(ChessGame::new_chess960(960).unwrap_err(), ChessError::NoSuchStartPosition(960));
+
+    let game = ChessGame::new_chess960(0).unwrap();
+    assert!(game.is_chess960());
+    assert_eq!(game.fen().unwrap(), "bbqnnrkr/pppppppp/8/8/8/8/PPPPPPPP/BBQNNRKR w KQkq - 0 1");
+    assert_eq!(game.shredder_fen().unwrap(), "bbqnnrkr/pppppppp/8/8/8/8/PPPPPPPP/BBQNNRKR w HFhf - 0 1");
+    assert!(!ChessGame::new().is_chess960());
+    assert_eq!(ChessGame::new().shredder_fen().unwrap(), "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w HAha - 0 1");
+}
+
+#[test]
+fn perft() {
+    let mut game = ChessGame::from_fen("bqnb1rkr/pp3ppp/3ppn2/2p5/5P2/P2P4/NPP1P1PP/BQ1BNRKR w HFhf - 2 9").unwrap();
+    assert!(game.is_chess960());
+    assert_eq!(game.perft(1), 21);
+    assert_eq!(game.perft(2), 528);
+    assert_eq!(game.perft(3), 12_189);
+    assert_eq!(game.perft(4), 326_672);
+
+    let mut game = ChessGame::from_fen("2nnrbkr/p1qppppp/8/1ppb4/6PP/3PP3/PPP2P2/BQNNRBKR w HEhe - 1 9").unwrap();
+    assert_eq!(game.perft(1), 21);
+    assert_eq!(game.perft(2), 807);
+    assert_eq!(game.perft(3), 18_002);
+}
+
+#[test]
+fn castling_onto_the_rook() {
+    // The king already stands on g1, so only the rook moves
+    let mut game = ChessGame::from_fen("4k3/8/8/8/8/8/8/R5KR w HA - 0 1").unwrap();
+    let castle = game.legal_moves().into_iter().find(|mv| mv.is_castle && mv.to.y() == 7).unwrap();
+    assert_eq!(game.san(&castle), "O-O");
+    assert_eq!(game.to_uci(&castle), "g1h1");
+    game.play_san("O-O").unwrap();
+    assert_eq!(game.fen().unwrap(), "4k3/8/8/8/8/8/8/R4RK1 b - - 1 1");
+    assert!(game.undo());
+    assert_eq!(game.shredder_fen().unwrap(), "4k3/8/8/8/8/8/8/R5KR w HA - 0 1");
+
+    // Queenside the king crosses to c1 and the rook on a1 lands on d1
+    game.make_move_uci("g1a1").unwrap();
+    assert_eq!(game.fen().unwrap(), "4k3/8/8/8/8/8/8/2KR3R b - - 1 1");
+
+    // Here the rook stays on f1 and the king passes over it
+    let mut game = ChessGame::from_fen("4k3/8/8/8/8/8/8/1K3R2 w F - 0 1").unwrap();
+    game.play_san("O-O").unwrap();
+    assert_eq!(game.fen().unwrap(), "4k3/8/8/8/8/8/8/5RK1 b - - 1 1");
+
+    // The rook's destination must be empty and the king's path unattacked
+    let game = ChessGame::from_fen("4k3/8/8/8/8/8/8/1R1NK2R w HB - 0 1").unwrap();
+    assert!(game.is_chess960());
+    assert!(game.parse_san("O-O-O").is_err());
+    assert!(game.parse_san("O-O").is_ok());
+    let game = ChessGame::from_fen("2r1k3/8/8/8/8/8/8/1R2K2R w HB - 0 1").unwrap();
+    assert!(game.parse_san("O-O-O").is_err());
+}
+
+#[test]
+fn no_castling_out_of_check() {
+    // The castled rook would stand between the king and the checking rook, the only attacker
+    for fen in ["1k6/8/8/8/8/8/8/RK5r w A - 0 1", "1k6/8/8/8/8/8/8/r5KR w H - 0 1"] {
+        let game = ChessGame::from_fen(fen).unwrap();
+        assert_eq!(game.status(), GameStatus::Check, "{}", fen);
+        assert!(!game.legal_moves().iter().any(|mv| mv.is_castle), "{}", fen);
+    }
+}
+
+#[test]
+fn fen_castling_fields() {
+    // X-FEN names the inner rook by its file
+    let fen = "rk2r3/8/8/8/8/8/8/RK2R2R w EQq - 0 1";
+    let game = ChessGame::from_fen(fen).unwrap();
+    assert_eq!(game.fen().unwrap(), fen);
+    assert_eq!(game.shredder_fen().unwrap(), "rk2r3/8/8/8/8/8/8/RK2R2R w EAa - 0 1");
+    assert_eq!(ChessGame::from_fen("rk2r3/8/8/8/8/8/8/RK2R2R w EAa - 0 1").unwrap().fen().unwrap(), fen);
+
+    // The standard setup keeps the standard rules, however it is written
+    let game = ChessGame::from_fen("r3k2r/8/8/8/8/8/8/R3K2R w HAha - 0 1").unwrap();
+    assert!(!game.is_chess960());
+    assert_eq!(game.fen().unwrap(), "r3k2r/8/8/8/8/8/8/R3K2R w KQkq - 0 1");
+
+    for bad in ["4k3/8/8/8/8/8/8/R3K2R w E - 0 1", "4k3/8/8/8/8/8/8/R3K2R w HH - 0 1", "4k3/8/8/8/4K3/8/8/R6R w A - 0 1"] {
+        assert!(ChessGame::from_fen(bad).is_err(), "{}", bad);
+    }
+}
+
+#[test]
+fn pgn_variant_tag() {
+    let mut game = ChessGame::new_chess960(518).unwrap();
+    for san in ["Nf3", "Nf6", "g3", "g6", "Bg2", "Bg7", "O-O"] {
+        game.play_san(san).unwrap();
+    }
+    assert_eq!(game.last_move_uci().as_deref(), Some("e1h1"));
+    let pgn = game.pgn(&[]);
+    assert!(pgn.contains("[Variant \"Chess960\"]") && !pgn.contains("[FEN"), "{}", pgn);
+    let tree = GameTree::from_pgn(&pgn).unwrap();
+    assert_eq!(tree.game().fen().unwrap(), game.fen().unwrap());
+    assert!(tree.game().is_chess960());
+}
@@ -0,0 +1,152 @@
+use chessgame::{ChessGame, Color};
+
+// Plays Nf3 Nf6 Ng1 Ng8, returning to the position it started from.
+fn shuffle_knights(game: &mut ChessGame) {
+    game.make_move(7, 6, 5, 5, None).unwrap();
+    game.make_move(0, 6, 2, 5, None).unwrap();
+    game.make_move(5, 5, 7, 6, None).unwrap();
+    game.make_move(2, 5, 0, 6, None).unwrap();
+}
+
+#[test]
+fn threefold_and_fivefold_repetition() {
+    let mut game = ChessGame::new();
+    shuffle_knights(&mut game);
+    assert!(!game.is_threefold_repetition());
+    shuffle_knights(&mut game);
+    assert!(game.is_threefold_repetition());
+    assert!(!game.is_fivefold_repetition());
+    shuffle_knights(&mut game);
+    shuffle_knights(&mut game);
+    assert!(game.is_fivefold_repetition());
+}
+
+#[test]
+fn pawn_moves_reset_repetition() {
+    let mut game = ChessGame::new();
+    shuffle_knights(&mut game);
+    game.make_move(6, 0, 5, 0, None).unwrap(); // a3
+    game.make_move(1, 0, 2, 0, None).unwrap(); // a6
+    shuffle_knights(&mut game);
+    assert!(!game.is_threefold_repetition());
+}
+
+#[test]
+fn lost_castling_rights_change_the_position() {
+    let mut game = ChessGame::new();
+    game.load_fen("r3k2r/8/8/8/8/8/8/R3K2R w KQkq - 0 1").unwrap();
+    // Ke2 Ke7 Ke1 Ke8 gives the same placement, but castling rights are gone
+    for _ in 0..2 {
+        game.make_move(7, 4, 6, 4, None).unwrap();
+        game.make_move(0, 4, 1, 4, None).unwrap();
+        game.make_move(6, 4, 7, 4, None).unwrap();
+        game.make_move(1, 4, 0, 4, None).unwrap();
+    }
+    assert!(!game.is_threefold_repetition());
+}
+
+fn game_from(fen: &str) -> ChessGame {
+    let mut game = ChessGame::new();
+    game.load_fen(fen).unwrap();
+    game
+}
+
+#[test]
+fn insufficient_material() {
+    assert!(game_from("8/8/4k3/8/8/3K4/8/8 w - - 0 1").is_insufficient_material());
+    assert!(game_from("8/8/4k3/8/8/3KN3/8/8 w - - 0 1").is_insufficient_material());
+    assert!(game_from("8/8/4kb2/8/8/3K4/8/8 b - - 0 1").is_insufficient_material());
+    // Bishops on the same square color
+    assert!(game_from("8/8/4k1b1/8/8/3K1B2/8/8 w - - 0 1").is_insufficient_material());
+    // Bishops on opposite colors can still mate in theory
+    assert!(!game_from("8/8/4kb2/8/8/3K1B2/8/8 w - - 0 1").is_insufficient_material());
+    assert!(!game_from("8/8/4k3/8/8/3KNN2/8/8 w - - 0 1").is_insufficient_material());
+    assert!(!game_from("8/8/4k3/8/8/3K4/4P3/8 w - - 0 1").is_insufficient_material());
+    assert!(game_from("8/8/4k3/8/8/3K4/8/8 w - - 0 1").is_game_over());
+}
+
+#[test]
+fn blocked_pawn_chains_are_dead() {
+    // Interlocked chain that neither king can get through
+    let dead = game_from("8/8/1k6/p1p1p1p1/P1P1P1P1/8/3K4/8 w - - 0 1");
+    assert!(dead.is_dead_position());
+    assert!(dead.is_game_over());
+    // A passed pawn can still promote
+    assert!(!game_from("8/7P/1k6/p1p1p1p1/P1P1P3/8/3K4/8 w - - 0 1").is_dead_position());
+    // The white king can walk round and win the a5 pawn
+    assert!(!game_from("8/8/1k6/p1p1p1p1/2P1P1P1/8/3K4/8 w - - 0 1").is_dead_position());
+    assert!(!ChessGame::new().is_dead_position());
+}
+
+#[test]
+fn status_reports_outcomes() {
+    use chessgame::{DrawReason, GameStatus};
+
+    let mut game = ChessGame::new();
+    assert_eq!(game.status(), GameStatus::Ongoing);
+    assert_eq!(game.outcome(), "*");
+
+    // Fool's mate
+    game.make_move(6, 5, 5, 5, None).unwrap();
+    game.make_move(1, 4, 3, 4, None).unwrap();
+    game.make_move(6, 6, 4, 6, None).unwrap();
+    game.make_move(0, 3, 4, 7, None).unwrap();
+    assert_eq!(game.status(), GameStatus::Checkmate { winner: Color::Black });
+    assert_eq!(game.outcome(), "0-1");
+    assert_eq!(
+        game.status_json(),
+        r#"{"status":"checkmate","winner":"black","reason":null,"outcome":"0-1"}"#
+    );
+
+    let stalemate = game_from("7k/5Q2/6K1/8/8/8/8/8 b - - 0 1");
+    assert_eq!(stalemate.status(), GameStatus::Stalemate);
+    assert_eq!(stalemate.outcome(), "1/2-1/2");
+
+    let bare_kings = game_from("8/8/4k3/8/8/3K4/8/8 w - - 0 1");
+    assert_eq!(bare_kings.status(), GameStatus::Draw(DrawReason::InsufficientMaterial));
+
+    let seventy_five = game_from("8/8/4k3/8/8/3K4/3Q4/8 w - - 150 120");
+    assert_eq!(seventy_five.status(), GameStatus::Draw(DrawReason::SeventyFiveMoveRule));
+}
+
+#[test]
+fn draws_resignations_and_timeouts_are_recorded() {
+    use chessgame::{DrawReason, GameStatus};
+
+    let mut game = ChessGame::new();
+    shuffle_knights(&mut game);
+    shuffle_knights(&mut game);
+    game.claim_draw().unwrap();
+    assert_eq!(game.status(), GameStatus::Draw(DrawReason::ThreefoldRepetition));
+    assert!(game.is_game_over());
+
+    let mut game = ChessGame::new();
+    game.resign(1).unwrap();
+    assert_eq!(game.status(), GameStatus::Resignation { winner: Color::Black });
+    assert_eq!(game.outcome(), "0-1");
+
+    let mut game = ChessGame::new();
+    game.flag_timeout(2).unwrap();
+    assert_eq!(game.outcome(), "1-0");
+}
+
+#[test]
+fn a_finished_game_keeps_its_result() {
+    use chessgame::{ChessError, GameStatus};
+
+    let mut game = ChessGame::new();
+    game.resign(1).unwrap();
+    assert_eq!(game.flag_timeout(1), Err(ChessError::GameOver));
+    assert_eq!(game.resign(2), Err(ChessError::GameOver));
+    assert_eq!(game.agree_draw(), Err(ChessError::GameOver));
+    assert_eq!(game.status(), GameStatus::Resignation { winner: Color::Black });
+
+    // Fool's mate, then a draw claim after the same moves twice is still refused
+    let mut game = ChessGame::new();
+    for san in ["f3", "e5", "g4", "Qh4#"] {
+        game.play_san(san).unwrap();
+    }
+    assert_eq!(game.resign(2), Err(ChessError::GameOver));
+    assert_eq!(game.claim_draw(), Err(ChessError::GameOver));
+    assert_eq!(game.outcome(), "0-1");
+}
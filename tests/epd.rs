@@ -0,0 +1,79 @@
+use std::time::Duration;
+
+use chessgame::{ChessError, ChessGame, Epd, EpdError};
+
+#[test]
+fn parses_operations() {
+    let epd = Epd::parse(
+        r#"r1bqkbnr/pppp1ppp/2n5/4p3/4P3/5N2/PPPP1PPP/RNBQKB1R w KQkq - bm Bb5 Bc4; am Ng5; id "Spanish \"or\" Italian";
+           c0 "main line"; c3 "three"; dm 12; acd 20; ce -15; hmvc 2; fmvn 3; pv Bb5 a6"#,
+    )
+    .unwrap();
+    assert_eq!(epd.game.fen().unwrap(), "r1bqkbnr/pppp1ppp/2n5/4p3/4P3/5N2/PPPP1PPP/RNBQKB1R w KQkq - 2 3");
+    let sans: Vec<String> = epd.best_moves.iter().map(|mv| epd.game.san(mv)).collect();
+    assert_eq!(sans, ["Bb5", "Bc4"]);
+    assert_eq!(epd.game.san(&epd.avoid_moves[0]), "Ng5");
+    assert_eq!(epd.id.as_deref(), Some("Spanish \"or\" Italian"));
+    assert_eq!(epd.comments[0].as_deref(), Some("main line"));
+    assert_eq!(epd.comments[3].as_deref(), Some("three"));
+    assert_eq!(epd.comments[1], None);
+    assert_eq!((epd.direct_mate, epd.analysis_depth, epd.centipawns), (Some(12), Some(20), Some(-15)));
+    // Unknown opcodes are kept as they are
+    assert_eq!(epd.operations.last().unwrap(), &("pv".to_string(), vec!["Bb5".to_string(), "a6".to_string()]));
+
+    let mut game = ChessGame::new();
+    game.load_epd("4k3/8/8/8/8/8/8/4K2R w K - id \"bare\"").unwrap();
+    assert_eq!(game.fen().unwrap(), "4k3/8/8/8/8/8/8/4K2R w K - 0 1");
+}
+
+#[test]
+fn rejects_bad_records() {
+    assert_eq!(Epd::parse("4k3/8/8/8/8/8/8/4K3 w -").unwrap_err(), EpdError::MissingFields);
+    assert!(matches!(Epd::parse("4k3/8/8/8/8/8/8/4K3 x - -"), Err(EpdError::InvalidPosition(_))));
+    assert_eq!(Epd::parse("4k3/8/8/8/8/8/8/4K3 w - - id \"open").unwrap_err(), EpdError::UnterminatedString);
+    assert_eq!(
+        Epd::parse("4k3/8/8/8/8/8/8/4K3 w - - bm Qh5;").unwrap_err(),
+        EpdError::InvalidOperand { opcode: "bm".to_string(), operand: "Qh5".to_string() }
+    );
+    assert_eq!(
+        Epd::parse("4k3/8/8/8/8/8/8/4K3 w - - acd deep;").unwrap_err(),
+        EpdError::InvalidOperand { opcode: "acd".to_string(), operand: "deep".to_string() }
+    );
+    assert!(matches!(ChessGame::new().load_epd("8/8 w - -"), Err(ChessError::InvalidEpd(_))));
+}
+
+#[test]
+fn search_finds_mates_and_material() {
+    let game = ChessGame::from_fen("6k1/5ppp/8/8/8/8/8/R5K1 w - - 0 1").unwrap();
+    let result = game.search(3, None).unwrap();
+    assert_eq!(game.to_uci(&result.best_move), "a1a8");
+    assert_eq!(result.score, chessgame::MATE - 1);
+    assert_eq!(game.best_move_uci(3).as_deref(), Some("a1a8"));
+
+    let game = ChessGame::from_fen("4k3/8/8/3q4/8/8/3R4/3K4 w - - 0 1").unwrap();
+    assert_eq!(game.best_move_uci(2).as_deref(), Some("d2d5"));
+    assert_eq!(ChessGame::from_fen("7k/5Q2/6K1/8/8/8/8/8 b - - 0 1").unwrap().search(3, None), None);
+
+    // A tiny budget still gives a move from the first depth
+    let game = ChessGame::new();
+    let result = game.search(64, Some(Duration::from_millis(20))).unwrap();
+    assert!(result.depth < 64);
+}
+
+#[test]
+fn win_at_chess_positions() {
+    let results = Epd::run_suite_file("tests/data/wac.epd", 4, None).unwrap();
+    // The first ten positions in order. At depth 4 the search misses the deeper
+    // combinations of WAC.002 and WAC.007.
+    assert_eq!(results.len(), 10);
+    let missed: Vec<&str> = results.iter().filter(|result| !result.passed).filter_map(|result| result.id.as_deref()).collect();
+    assert_eq!(missed, ["WAC.002", "WAC.007"]);
+
+    let suite = "# a comment\n\n4k3/8/8/8/8/8/8/4K3 w - - bm Qh5;\n6k1/5ppp/8/8/8/8/8/R5K1 w - - am Kf1; id \"avoid\"\n";
+    let results = Epd::run_suite(suite, 2, None);
+    assert_eq!(results[0].line, 3);
+    assert!(!results[0].passed && results[0].error.is_some());
+    assert_eq!(results[1].id.as_deref(), Some("avoid"));
+    assert_eq!(results[1].found.as_deref(), Some("Ra8#"));
+    assert!(results[1].passed);
+}
@@ -0,0 +1,63 @@
+use chessgame::{ChessError, ChessGame, Color, FenErrorKind, IllegalMoveReason, PieceKind, PromotionError, Square};
+
+fn sq(name: &str) -> Square {
+    Square::from_algebraic(name).unwrap()
+}
+
+#[test]
+fn move_errors_are_typed() {
+    let mut game = ChessGame::new();
+    assert_eq!(game.make_move(4, 4, 3, 4, None), Err(ChessError::EmptySquare(sq("e4"))));
+    assert_eq!(game.make_move(1, 4, 3, 4, None), Err(ChessError::WrongTurn(Color::White)));
+    assert_eq!(game.make_move(6, 4, 3, 4, None), Err(ChessError::IllegalMove { from: sq("e2"), to: sq("e5"), reason: IllegalMoveReason::WrongShape(PieceKind::Pawn) }));
+    assert_eq!(game.make_move(8, 0, 7, 0, None), Err(ChessError::OffBoard { x: 8, y: 0 }));
+    assert_eq!(
+        game.make_move(6, 4, 4, 4, Some(5)),
+        Err(ChessError::InvalidPromotion(PromotionError::NotAPromotion))
+    );
+    // None of the failures changed the game
+    assert_eq!(game.fen().unwrap(), "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1");
+}
+
+#[test]
+fn promotion_errors_are_typed() {
+    let mut game = ChessGame::new();
+    game.load_fen("8/4P1k1/8/8/8/8/6K1/8 w - - 0 1").unwrap();
+    assert_eq!(game.promote_pawn(0, 4, 5), Err(ChessError::InvalidPromotion(PromotionError::NonePending)));
+
+    game.make_move(1, 4, 0, 4, None).unwrap();
+    assert_eq!(game.make_move(6, 6, 5, 6, None), Err(ChessError::PromotionPending));
+    assert_eq!(game.fen(), Err(ChessError::PromotionPending));
+    assert_eq!(game.promote_pawn(0, 5, 5), Err(ChessError::InvalidPromotion(PromotionError::WrongSquare)));
+    // A black queen, and a white king
+    assert_eq!(game.promote_pawn(0, 4, 11), Err(ChessError::InvalidPromotion(PromotionError::InvalidPiece)));
+    assert_eq!(game.promote_pawn(0, 4, 6), Err(ChessError::InvalidPromotion(PromotionError::InvalidPiece)));
+    game.promote_pawn(0, 4, 5).unwrap();
+}
+
+#[test]
+fn fen_and_game_state_errors_are_typed() {
+    let mut game = ChessGame::new();
+    match game.load_fen("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNX w KQkq - 0 1") {
+        Err(ChessError::InvalidFen(e)) => assert_eq!(e.kind, FenErrorKind::UnknownPiece('X')),
+        other => panic!("expected a FEN error, got {:?}", other),
+    }
+    assert_eq!(game.claim_draw(), Err(ChessError::NoDrawToClaim));
+
+    assert_eq!(game.resign(3), Err(ChessError::UnknownColor(3)));
+    assert_eq!(game.flag_timeout(0), Err(ChessError::UnknownColor(0)));
+    assert!(!game.is_game_over());
+    game.resign(1).unwrap();
+    assert_eq!(game.make_move(6, 4, 4, 4, None), Err(ChessError::GameOver));
+}
+
+#[test]
+fn errors_describe_themselves() {
+    let error: Box<dyn std::error::Error> = Box::new(ChessError::WrongTurn(Color::White));
+    assert_eq!(error.to_string(), "not Black's turn");
+    assert!(error.source().is_none());
+
+    let game = ChessGame::from_fen("8/8 w - -");
+    let error = ChessError::from(game.unwrap_err());
+    assert!(std::error::Error::source(&error).is_some());
+}
@@ -0,0 +1,60 @@
+use chessgame::{ChessGame, IllegalMoveReason, PieceKind, Square};
+use IllegalMoveReason::*;
+
+fn sq(name: &str) -> Square {
+    Square::from_algebraic(name).unwrap()
+}
+
+fn explain(game: &ChessGame, from: &str, to: &str) -> Result<(), IllegalMoveReason> {
+    game.explain_move(sq(from), sq(to))
+}
+
+#[test]
+fn basic_reasons() {
+    let game = ChessGame::new();
+    assert_eq!(explain(&game, "e4", "e5"), Err(EmptyStartSquare));
+    assert_eq!(explain(&game, "e7", "e5"), Err(WrongTurn));
+    assert_eq!(explain(&game, "a1", "a2"), Err(OwnPieceOnTarget));
+    assert_eq!(explain(&game, "g1", "g3"), Err(WrongShape(PieceKind::Knight)));
+    assert_eq!(explain(&game, "e2", "d3"), Err(WrongShape(PieceKind::Pawn)));
+    assert_eq!(explain(&game, "d1", "d3"), Err(PathBlocked(sq("d2"))));
+    assert_eq!(explain(&game, "e2", "e4"), Ok(()));
+}
+
+#[test]
+fn pawn_and_pin_reasons() {
+    let game = ChessGame::from_fen("4k3/4r3/8/8/8/3p4/3PB3/4K3 w - - 0 1").unwrap();
+    assert_eq!(explain(&game, "d2", "d3"), Err(PawnBlocked(sq("d3"))));
+    assert_eq!(explain(&game, "d2", "d4"), Err(PawnBlocked(sq("d3"))));
+    // The bishop is pinned to the king by the rook on e7
+    assert_eq!(explain(&game, "e2", "d3"), Err(LeavesKingInCheck));
+}
+
+#[test]
+fn castling_reasons() {
+    let game = ChessGame::from_fen("4k3/8/8/8/8/8/8/R3KB1R w KQ - 0 1").unwrap();
+    assert_eq!(explain(&game, "e1", "g1"), Err(PathBlocked(sq("f1"))));
+
+    let game = ChessGame::from_fen("4kr2/8/8/8/8/8/8/R3K2R w Q - 0 1").unwrap();
+    assert_eq!(explain(&game, "e1", "g1"), Err(CastlingRightsLost));
+    assert_eq!(explain(&game, "e1", "c1"), Ok(()));
+
+    let game = ChessGame::from_fen("4kr2/8/8/8/8/8/8/R3K2R w KQ - 0 1").unwrap();
+    assert_eq!(explain(&game, "e1", "g1"), Err(CastlingThroughAttackedSquare(sq("f1"))));
+
+    let game = ChessGame::from_fen("4k3/8/8/8/8/8/4r3/R3K2R w KQ - 0 1").unwrap();
+    assert_eq!(explain(&game, "e1", "c1"), Err(CastlingThroughAttackedSquare(sq("e1"))));
+}
+
+#[test]
+fn reasons_as_json() {
+    let game = ChessGame::new();
+    assert_eq!(game.explain_move_json(6, 4, 4, 4), "null");
+    assert!(game.explain_move_json(8, 0, 7, 0).starts_with(r#"{"reason":"out_of_bounds""#));
+    assert!(game.explain_move_json(6, 4, 6, -1).starts_with(r#"{"reason":"out_of_bounds""#));
+    assert!(!game.is_valid_move(8, 0, 7, 0));
+    assert_eq!(
+        game.explain_move_json(7, 3, 5, 3),
+        r#"{"reason":"path_blocked","square":[6,3],"message":"the path is blocked at d2"}"#
+    );
+}
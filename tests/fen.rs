@@ -0,0 +1,78 @@
+use chessgame::ChessGame;
+
+#[test]
+fn clocks_round_trip() {
+    let fen = "r1bqkbnr/pppp1ppp/2n5/4p3/4P3/5N2/PPPP1PPP/RNBQKB1R w KQkq - 17 42";
+    let mut game = ChessGame::new();
+    game.load_fen(fen).unwrap();
+    assert_eq!(game.get_halfmove_clock(), 17);
+    assert_eq!(game.get_fullmove_number(), 42);
+    assert_eq!(game.fen().unwrap(), fen);
+}
+
+#[test]
+fn clocks_follow_the_moves() {
+    let mut game = ChessGame::new();
+    game.make_move(7, 6, 5, 5, None).unwrap(); // Nf3
+    assert_eq!(game.fen().unwrap(), "rnbqkbnr/pppppppp/8/8/8/5N2/PPPPPPPP/RNBQKB1R b KQkq - 1 1");
+    game.make_move(1, 4, 3, 4, None).unwrap(); // e5 resets the halfmove clock
+    assert_eq!(game.fen().unwrap(), "rnbqkbnr/pppp1ppp/8/4p3/8/5N2/PPPPPPPP/RNBQKB1R w KQkq e6 0 2");
+    game.make_move(5, 5, 3, 4, None).unwrap(); // Nxe5
+    assert_eq!(game.get_halfmove_clock(), 0);
+    assert_eq!(game.get_fullmove_number(), 2);
+}
+
+#[test]
+fn rejects_malformed_fens_with_position() {
+    use chessgame::{FenErrorKind, FenField};
+
+    let error = |fen: &str| ChessGame::from_fen(fen).unwrap_err();
+
+    let e = error("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq");
+    assert_eq!(e.kind, FenErrorKind::WrongFieldCount(3));
+
+    let e = error("rnbqkbnr/pppppppp/9/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1");
+    assert_eq!((e.field, e.offset), (FenField::Placement, 18));
+    assert_eq!(e.kind, FenErrorKind::UnknownPiece('9'));
+
+    let e = error("rnbqkbnr/ppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1");
+    assert_eq!(e.kind, FenErrorKind::BadRankLength { rank: 7, squares: 7 });
+    assert_eq!(e.offset, 9);
+
+    let e = error("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNRR w KQkq - 0 1");
+    assert_eq!(e.kind, FenErrorKind::BadRankLength { rank: 1, squares: 9 });
+
+    let e = error("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNX w KQkq - 0 1");
+    assert_eq!((e.kind, e.offset), (FenErrorKind::UnknownPiece('X'), 42));
+
+    let e = error("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR x KQkq - 0 1");
+    assert_eq!(e.field, FenField::SideToMove);
+
+    let e = error("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkx - 0 1");
+    assert_eq!((e.field, e.offset, e.kind), (FenField::Castling, 49, FenErrorKind::InvalidCastling('x')));
+
+    let e = error("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq e9 0 1");
+    assert_eq!(e.field, FenField::EnPassant);
+    // No pawn has just made a double step to e4
+    let e = error("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR b KQkq e3 0 1");
+    assert_eq!(e.kind, FenErrorKind::ImpossibleEnPassant("e3".to_string()));
+
+    let e = error("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - zero 1");
+    assert_eq!((e.field, e.kind), (FenField::HalfmoveClock, FenErrorKind::NonNumericClock("zero".to_string())));
+    let e = error("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 -1");
+    assert_eq!(e.field, FenField::FullmoveNumber);
+    let e = error("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - +3 1");
+    assert_eq!((e.field, e.kind), (FenField::HalfmoveClock, FenErrorKind::NonNumericClock("+3".to_string())));
+    let e = error("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 0");
+    assert_eq!((e.field, e.kind), (FenField::FullmoveNumber, FenErrorKind::ZeroFullmoveNumber));
+
+    // "44" adds up to a full rank but isn't how FEN writes one
+    let e = error("rnbqkbnr/pppppppp/44/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1");
+    assert_eq!((e.field, e.offset, e.kind), (FenField::Placement, 19, FenErrorKind::ConsecutiveDigits));
+}
+
+#[test]
+fn accepts_fens_without_clocks() {
+    let game = ChessGame::from_fen("rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq e3").unwrap();
+    assert_eq!(game.fen().unwrap(), "rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq e3 0 1");
+}
@@ -0,0 +1,82 @@
+use chessgame::{ChessGame, PieceKind, Square};
+
+fn play(game: &mut ChessGame, from: &str, to: &str) {
+    let (from, to) = (Square::from_algebraic(from).unwrap(), Square::from_algebraic(to).unwrap());
+    game.make_move(from.x(), from.y(), to.x(), to.y(), None).unwrap();
+}
+
+#[test]
+fn undo_restores_every_position_exactly() {
+    // Castling rights, an en passant capture, a capture and a promotion along the way
+    let mut game = ChessGame::from_fen("r3k2r/1P6/8/8/5p2/8/4P3/R3K2R w KQkq - 3 20").unwrap();
+    let mut fens = vec![game.fen().unwrap()];
+    for &(from, to) in &[("e2", "e4"), ("f4", "e3"), ("e1", "g1"), ("a8", "a2"), ("b7", "b8")] {
+        play(&mut game, from, to);
+        if game.has_pending_promotion() {
+            game.promote_pawn(0, 1, 2).unwrap(); // Rook
+        }
+        fens.push(game.fen().unwrap());
+    }
+    assert_eq!(game.ply_count(), 5);
+    let last = game.history()[4];
+    assert_eq!(last.promotion, Some(PieceKind::Rook));
+    assert!(game.history()[1].is_en_passant && game.history()[2].is_castle);
+
+    for expected in fens.iter().rev().skip(1) {
+        assert!(game.undo());
+        assert_eq!(&game.fen().unwrap(), expected);
+    }
+    assert!(!game.undo());
+    assert_eq!(game.ply_count(), 0);
+
+    for expected in fens.iter().skip(1) {
+        assert!(game.redo());
+        assert_eq!(&game.fen().unwrap(), expected);
+    }
+    assert!(!game.redo());
+}
+
+#[test]
+fn a_new_move_drops_the_redo_line() {
+    let mut game = ChessGame::new();
+    play(&mut game, "e2", "e4");
+    play(&mut game, "e7", "e5");
+    assert!(game.undo());
+    play(&mut game, "c7", "c5");
+    assert!(!game.redo());
+    assert_eq!(game.ply_count(), 2);
+    assert_eq!(game.history_json().matches("\"from\"").count(), 2);
+
+    // Loading a position does the same
+    assert!(game.undo());
+    game.load_fen("4k3/8/8/8/8/8/8/4K3 w - - 0 1").unwrap();
+    assert!(!game.redo());
+}
+
+#[test]
+fn undo_after_change_turn_gives_the_move_back_to_its_player() {
+    let mut game = ChessGame::new();
+    game.play_san("e4").unwrap();
+    game.change_turn().unwrap();
+    assert_eq!(game.get_current_turn(), 1);
+    assert!(game.undo());
+    assert_eq!(game.fen().unwrap(), ChessGame::new().fen().unwrap());
+}
+
+#[test]
+fn undo_reopens_a_finished_game_and_cancels_a_pending_promotion() {
+    let mut game = ChessGame::new();
+    for &(from, to) in &[("f2", "f3"), ("e7", "e5"), ("g2", "g4"), ("d8", "h4")] {
+        play(&mut game, from, to);
+    }
+    assert!(game.is_game_over());
+    assert!(game.undo());
+    assert!(!game.is_game_over());
+
+    let mut game = ChessGame::from_fen("8/4P1k1/8/8/8/8/6K1/8 w - - 0 1").unwrap();
+    play(&mut game, "e7", "e8");
+    assert!(game.has_pending_promotion());
+    assert!(game.undo());
+    assert!(!game.has_pending_promotion());
+    assert_eq!(game.fen().unwrap(), "8/4P1k1/8/8/8/8/6K1/8 w - - 0 1");
+}
@@ -0,0 +1,60 @@
+use chessgame::{CastleSide, ChessGame, Color, MoveTarget, Piece, PieceKind, Square};
+
+fn sq(name: &str) -> Square {
+    Square::from_algebraic(name).unwrap()
+}
+
+fn target(game: &ChessGame, from: &str, to: &str) -> MoveTarget {
+    game.move_targets(sq(from))
+        .into_iter()
+        .find(|target| target.to == sq(to))
+        .unwrap_or_else(|| panic!("{}-{} is not legal", from, to))
+}
+
+#[test]
+fn captures_and_en_passant_are_flagged() {
+    // Black has just played d7-d5 next to the e5 pawn
+    let game = ChessGame::from_fen("4k3/8/2n5/3pP3/8/8/8/4K2R w K d6 0 1").unwrap();
+    let en_passant = target(&game, "e5", "d6");
+    assert!(en_passant.capture && en_passant.en_passant);
+    assert_eq!(en_passant.captured, Some(Piece::new(Color::Black, PieceKind::Pawn)));
+
+    let push = target(&game, "e5", "e6");
+    assert!(!push.capture && !push.en_passant && !push.check);
+    assert_eq!(push.captured, None);
+
+    let castle = target(&game, "e1", "g1");
+    assert_eq!(castle.castle, Some(CastleSide::Kingside));
+    assert!(!castle.capture);
+
+    let check = target(&game, "h1", "h8");
+    assert!(check.check && !check.checkmate);
+}
+
+#[test]
+fn promotions_appear_once_and_checks_are_flagged() {
+    let game = ChessGame::from_fen("6k1/1P3ppp/8/8/8/8/5PPP/R5K1 w - - 0 1").unwrap();
+    let targets = game.move_targets(sq("b7"));
+    assert_eq!(targets.len(), 1);
+    assert!(targets[0].promotion);
+    // A queen on b8 mates on the back rank
+    assert!(targets[0].check && targets[0].checkmate);
+
+    let mate = target(&game, "a1", "a8");
+    assert!(mate.check && mate.checkmate);
+    assert!(!target(&game, "a1", "a7").check);
+}
+
+#[test]
+fn targets_as_json() {
+    let game = ChessGame::new();
+    assert_eq!(
+        game.get_moves_json(6, 4),
+        concat!(
+            r#"[{"to":[5,4],"capture":false,"captured":null,"en_passant":false,"castle":null,"promotion":false,"check":false,"checkmate":false},"#,
+            r#"{"to":[4,4],"capture":false,"captured":null,"en_passant":false,"castle":null,"promotion":false,"check":false,"checkmate":false}]"#,
+        )
+    );
+    assert_eq!(game.get_moves_json(4, 4), "[]");
+    assert_eq!(game.get_moves_json(9, 9), "[]");
+}
@@ -0,0 +1,106 @@
+//! Perft node counts for the standard reference positions.
+//! Counts are the published values from the Chess Programming Wiki and
+//! Martin Sedlak's edge-case collection.
+
+use chessgame::ChessGame;
+
+fn perft(fen: &str, depth: u32) -> u64 {
+    let mut game = ChessGame::new();
+    game.load_fen(fen).unwrap();
+    game.perft(depth)
+}
+
+#[test]
+fn start_position() {
+    let mut game = ChessGame::new();
+    assert_eq!(game.perft(1), 20);
+    assert_eq!(game.perft(2), 400);
+    assert_eq!(game.perft(3), 8_902);
+    assert_eq!(game.perft(4), 197_281);
+}
+
+#[test]
+fn kiwipete() {
+    let fen = "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1";
+    assert_eq!(perft(fen, 1), 48);
+    assert_eq!(perft(fen, 2), 2_039);
+    assert_eq!(perft(fen, 3), 97_862);
+}
+
+#[test]
+fn position_3_en_passant_and_pins() {
+    let fen = "8/2p5/3p4/KP5r/1R3p1k/8/4P1P1/8 w - - 0 1";
+    assert_eq!(perft(fen, 1), 14);
+    assert_eq!(perft(fen, 2), 191);
+    assert_eq!(perft(fen, 3), 2_812);
+    assert_eq!(perft(fen, 4), 43_238);
+    assert_eq!(perft(fen, 5), 674_624);
+}
+
+#[test]
+fn position_4_castling_and_promotion() {
+    let fen = "r3k2r/Pppp1ppp/1b3nbN/nP6/BBP1P3/q4N2/Pp1P2PP/R2Q1RK1 w kq - 0 1";
+    assert_eq!(perft(fen, 1), 6);
+    assert_eq!(perft(fen, 2), 264);
+    assert_eq!(perft(fen, 3), 9_467);
+    assert_eq!(perft(fen, 4), 422_333);
+}
+
+#[test]
+fn position_5() {
+    let fen = "rnbq1k1r/pp1Pbppp/2p5/8/2B5/8/PPP1NnPP/RNBQK2R w KQ - 1 8";
+    assert_eq!(perft(fen, 1), 44);
+    assert_eq!(perft(fen, 2), 1_486);
+    assert_eq!(perft(fen, 3), 62_379);
+}
+
+#[test]
+fn position_6() {
+    let fen = "r4rk1/1pp1qppp/p1np1n2/2b1p1B1/2B1P1b1/P1NP1N2/1PP1QPPP/R4RK1 w - - 0 10";
+    assert_eq!(perft(fen, 1), 46);
+    assert_eq!(perft(fen, 2), 2_079);
+    assert_eq!(perft(fen, 3), 89_890);
+}
+
+#[test]
+fn illegal_en_passant_pins() {
+    assert_eq!(perft("3k4/3p4/8/K1P4r/8/8/8/8 b - - 0 1", 6), 1_134_888);
+    assert_eq!(perft("8/8/4k3/8/2p5/8/B2P2K1/8 w - - 0 1", 6), 1_015_133);
+    assert_eq!(perft("8/8/1k6/2b5/2pP4/8/5K2/8 b - d3 0 1", 6), 1_440_467);
+}
+
+#[test]
+fn castling_edge_cases() {
+    // Castling that gives check
+    assert_eq!(perft("5k2/8/8/8/8/8/8/4K2R w K - 0 1", 6), 661_072);
+    assert_eq!(perft("3k4/8/8/8/8/8/8/R3K3 w Q - 0 1", 6), 803_711);
+    // Castling rights lost by captures, and castling through attacked squares
+    assert_eq!(perft("r3k2r/1b4bq/8/8/8/8/7B/R3K2R w KQkq - 0 1", 4), 1_274_206);
+    assert_eq!(perft("r3k2r/8/3Q4/8/8/5q2/8/R3K2R b KQkq - 0 1", 4), 1_720_476);
+}
+
+#[test]
+fn promotion_edge_cases() {
+    assert_eq!(perft("2K2r2/4P3/8/8/8/8/8/3k4 w - - 0 1", 6), 3_821_001);
+    assert_eq!(perft("4k3/1P6/8/8/8/8/K7/8 w - - 0 1", 6), 217_342);
+    assert_eq!(perft("8/P1k5/K7/8/8/8/8/8 w - - 0 1", 6), 92_683);
+}
+
+#[test]
+fn checks_and_stalemates() {
+    assert_eq!(perft("8/8/1P2K3/8/2n5/1q6/8/5k2 b - - 0 1", 5), 1_004_658);
+    assert_eq!(perft("K1k5/8/P7/8/8/8/8/8 w - - 0 1", 6), 2_217);
+    assert_eq!(perft("8/k1P5/8/1K6/8/8/8/8 w - - 0 1", 7), 567_584);
+    assert_eq!(perft("8/8/2k5/5q2/5n2/8/5K2/8 b - - 0 1", 4), 23_527);
+}
+
+#[test]
+fn perft_leaves_the_game_untouched() {
+    let fen = "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1";
+    let mut game = ChessGame::new();
+    game.load_fen(fen).unwrap();
+    let divide = game.perft_divide(2);
+    assert_eq!(divide.len(), 48);
+    assert_eq!(divide.iter().map(|(_, nodes)| nodes).sum::<u64>(), 2_039);
+    assert_eq!(game.fen().unwrap(), fen);
+}
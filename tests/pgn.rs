@@ -0,0 +1,170 @@
+use chessgame::{ChessError, ChessGame, GameTree, PgnError, PgnErrorKind, PgnGame, SanError};
+
+fn played(fen: Option<&str>, moves: &[&str]) -> ChessGame {
+    let mut game = ChessGame::new();
+    if let Some(fen) = fen {
+        game.load_fen(fen).unwrap();
+    }
+    for san in moves {
+        game.play_san(san).unwrap();
+    }
+    game
+}
+
+#[test]
+fn finished_game_with_tags() {
+    let game = played(None, &["f3", "e5", "g4", "Qh4#"]);
+    let pgn = game.pgn(&[("White", "alice"), ("Black", "bob \"the rook\""), ("TimeControl", "300"), ("Result", "1-0")]);
+    assert_eq!(
+        pgn,
+        "[Event \"?\"]\n\
+         [Site \"?\"]\n\
+         [Date \"????.??.??\"]\n\
+         [Round \"?\"]\n\
+         [White \"alice\"]\n\
+         [Black \"bob \\\"the rook\\\"\"]\n\
+         [Result \"0-1\"]\n\
+         [TimeControl \"300\"]\n\
+         \n\
+         1. f3 e5 2. g4 Qh4# 0-1\n"
+    );
+}
+
+#[test]
+fn custom_start_gets_setup_and_fen() {
+    let fen = "r3k3/8/8/8/8/8/8/4K3 b q - 0 12";
+    let game = played(Some(fen), &["O-O-O", "Ke2"]);
+    let pgn = game.pgn(&[]);
+    assert!(pgn.contains("[SetUp \"1\"]\n[FEN \"r3k3/8/8/8/8/8/8/4K3 b q - 0 12\"]\n"));
+    assert!(pgn.ends_with("\n\n12... O-O-O 13. Ke2 *\n"));
+
+    assert!(!ChessGame::new().pgn(&[]).contains("SetUp"));
+}
+
+#[test]
+fn long_movetext_wraps() {
+    let mut moves = Vec::new();
+    // The pawn moves keep it short of a fivefold repetition, which would end the game by itself
+    for round in 0..4 {
+        if round == 2 {
+            moves.extend_from_slice(&["e4", "e5"]);
+        }
+        moves.extend_from_slice(&["Nf3", "Nf6", "Ng1", "Ng8"]);
+    }
+    let mut game = played(None, &moves);
+    game.agree_draw().unwrap();
+    let pgn = game.pgn(&[]);
+    let movetext = pgn.split("\n\n").nth(1).unwrap();
+    assert!(movetext.lines().count() > 1);
+    assert!(movetext.lines().all(|line| line.len() < 80));
+    assert!(movetext.starts_with("1. Nf3 Nf6 2. Ng1 Ng8 3. Nf3"));
+    assert!(movetext.ends_with("9. Ng1 Ng8 1/2-1/2\n"));
+}
+
+#[test]
+fn tags_from_json() {
+    let game = played(None, &["e4"]);
+    assert_eq!(game.to_pgn(r#"{"White": "alice"}"#).unwrap(), game.pgn(&[("White", "alice")]));
+    assert!(matches!(game.to_pgn(r#"{"Round": 3}"#), Err(ChessError::InvalidTags(_))));
+    assert!(matches!(game.to_pgn("White"), Err(ChessError::InvalidTags(_))));
+}
+
+const TWO_GAMES: &str = r#"% exported from the club database
+[Event "Club \"open\""]
+[White "alice"]
+[Black "bob"]
+[Result "1-0"]
+
+1. e4 {best by test} e5 2. Nf3 (2. f4 exf4 (2... d5 3. exd5) 3. Nf3) 2... Nc6 $1
+3. Bb5 ; the Spanish
+a6 4.Ba4 1-0
+
+[Event "Second"]
+[SetUp "1"]
+[FEN "4k3/8/8/8/8/8/8/R3K3 w Q - 0 30"]
+
+30. O-O-O Ke7 *
+"#;
+
+#[test]
+fn reads_every_game_of_a_file() {
+    let games = PgnGame::read_all(TWO_GAMES).unwrap();
+    assert_eq!(games.len(), 2);
+
+    let first = &games[0];
+    assert_eq!(first.tag("Event"), Some("Club \"open\""));
+    assert_eq!(first.tag("White"), Some("alice"));
+    assert_eq!(first.result, "1-0");
+    assert_eq!(first.game.ply_count(), 7);
+    assert_eq!(first.game.fen().unwrap(), "r1bqkbnr/1ppp1ppp/p1n5/4p3/B3P3/5N2/PPPP1PPP/RNBQK2R b KQkq - 1 4");
+
+    let second = &games[1];
+    assert_eq!(second.result, "*");
+    assert_eq!(second.game.fen().unwrap(), "8/4k3/8/8/8/8/8/2KR4 w - - 2 31");
+}
+
+#[test]
+fn written_pgn_reads_back() {
+    let game = played(Some("r3k3/8/8/8/8/8/8/4K3 b q - 0 12"), &["O-O-O", "Ke2", "Rd2+", "Kxd2"]);
+    let read = ChessGame::from_pgn(&game.pgn(&[("White", "alice")])).unwrap();
+    assert_eq!(read.fen().unwrap(), game.fen().unwrap());
+    assert_eq!(read.history(), game.history());
+
+    let mut loaded = ChessGame::new();
+    loaded.load_pgn(TWO_GAMES).unwrap();
+    assert_eq!(loaded.ply_count(), 7);
+}
+
+#[test]
+fn move_numbers_without_periods() {
+    let game = ChessGame::from_pgn("1 e4 e5 2 Nf3 *").unwrap();
+    assert_eq!(game.history(), played(None, &["e4", "e5", "Nf3"]).history());
+}
+
+#[test]
+fn imported_result_survives_until_a_move() {
+    let pgn = "[Result \"1-0\"]\n\n1. e4 e5 1-0";
+    let game = ChessGame::from_pgn(pgn).unwrap();
+    assert!(game.pgn(&[]).contains("[Result \"1-0\"]") && game.pgn(&[]).ends_with("1. e4 e5 1-0\n"));
+    assert!(GameTree::from_pgn(pgn).unwrap().pgn(&[]).ends_with("1-0\n"));
+    let mut loaded = ChessGame::new();
+    loaded.load_pgn(pgn).unwrap();
+    assert!(loaded.pgn(&[]).ends_with("1-0\n"));
+
+    // The tag stands in for a missing token
+    assert!(ChessGame::from_pgn("[Result \"1/2-1/2\"]\n\n1. e4 e5").unwrap().pgn(&[]).ends_with("1/2-1/2\n"));
+
+    // Playing on or taking a move back leaves the result to the position again
+    let mut game = ChessGame::from_pgn(pgn).unwrap();
+    game.play_san("Nf3").unwrap();
+    assert!(game.pgn(&[]).ends_with("*\n"));
+    let mut game = ChessGame::from_pgn(pgn).unwrap();
+    assert!(game.undo());
+    assert!(game.pgn(&[]).ends_with("*\n"));
+}
+
+#[test]
+fn errors_name_the_game_and_ply() {
+    let error = |pgn: &str| PgnGame::read_all(pgn).unwrap_err();
+
+    let bad_move = format!("{}\n[Event \"Third\"]\n\n1. e4 e5 2. Ke3 *", TWO_GAMES);
+    assert_eq!(
+        error(&bad_move),
+        PgnError { game: 2, ply: Some(3), kind: PgnErrorKind::IllegalMove(SanError::NoLegalMove("Ke3".to_string())) }
+    );
+    // Moves inside variations are checked too
+    assert_eq!(error("1. e4 e5 (1... Nf3) *").ply, Some(2));
+
+    assert_eq!(error("1. e4 { never closed").kind, PgnErrorKind::UnterminatedComment);
+    assert_eq!(error("1. e4 $abc *").kind, PgnErrorKind::InvalidNag { nag: "$abc".to_string(), offset: 6 });
+    assert_eq!(error("{ é } 1. e4 $300 *").kind, PgnErrorKind::InvalidNag { nag: "$300".to_string(), offset: 12 });
+    assert_eq!(error("1. e4 $+1 *").kind, PgnErrorKind::InvalidNag { nag: "$+1".to_string(), offset: 6 });
+    assert_eq!(error("1. e4 (1. d4 *").kind, PgnErrorKind::UnbalancedVariation);
+    assert_eq!(error("1. e4 ) *").kind, PgnErrorKind::UnbalancedVariation);
+    assert_eq!(error("(1. d4) 1. e4 *").kind, PgnErrorKind::UnbalancedVariation);
+    assert_eq!(error("[White alice]\n1. e4 *").kind, PgnErrorKind::InvalidTag("[White alice]".to_string()));
+    assert!(matches!(error("[FEN \"8/8 w - -\"]\n*").kind, PgnErrorKind::InvalidFen(_)));
+
+    assert_eq!(PgnGame::read_all("").unwrap().len(), 0);
+    assert!(matches!(ChessGame::new().load_pgn("  "), Err(ChessError::InvalidPgn(PgnError { kind: PgnErrorKind::NoGame, .. }))));
+}
@@ -0,0 +1,37 @@
+use chessgame::ChessGame;
+
+const FEN: &str = "8/4P1k1/8/8/8/8/6K1/8 w - - 0 1";
+
+#[test]
+fn promotion_applies_in_one_step() {
+    let mut game = ChessGame::new();
+    game.load_fen(FEN).unwrap();
+    assert_eq!(game.make_move(1, 4, 0, 4, Some(5)).unwrap(), None);
+    assert!(!game.has_pending_promotion());
+    assert_eq!(game.get_piece(0, 4), 5);
+    assert_eq!(game.fen().unwrap(), "4Q3/6k1/8/8/8/8/6K1/8 b - - 0 1");
+}
+
+#[test]
+fn two_step_promotion_holds_the_move_back() {
+    let mut game = ChessGame::new();
+    game.load_fen(FEN).unwrap();
+    assert_eq!(game.make_move(1, 4, 0, 4, None).unwrap(), Some(vec![0, 4]));
+    assert!(game.has_pending_promotion());
+    // Nothing has moved yet and it is still White's turn
+    assert_eq!(game.get_piece(1, 4), 1);
+    assert_eq!(game.get_current_turn(), 1);
+
+    game.promote_pawn(0, 4, 3).unwrap();
+    assert!(!game.has_pending_promotion());
+    assert_eq!(game.fen().unwrap(), "4N3/6k1/8/8/8/8/6K1/8 b - - 0 1");
+}
+
+#[test]
+fn cancelled_promotion_leaves_the_position_alone() {
+    let mut game = ChessGame::new();
+    game.load_fen(FEN).unwrap();
+    game.make_move(1, 4, 0, 4, None).unwrap();
+    game.cancel_promotion();
+    assert_eq!(game.fen().unwrap(), FEN);
+}
@@ -0,0 +1,92 @@
+use chessgame::{ChessError, ChessGame, SanError};
+
+fn sans(fen: &str, moves: &[&str]) -> ChessGame {
+    let mut game = ChessGame::from_fen(fen).unwrap();
+    for san in moves {
+        game.play_san(san).unwrap();
+    }
+    game
+}
+
+#[test]
+fn every_legal_move_round_trips_through_san() {
+    let fens = [
+        "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+        "8/2p5/3p4/KP5r/1R3p1k/8/4P1P1/8 w - - 0 1",
+        "r3k2r/Pppp1ppp/1b3nbN/nP6/BBP1P3/q4N2/Pp1P2PP/R2Q1RK1 w kq - 0 1",
+        "Q1Q5/8/Q7/8/7k/8/8/4K3 w - - 0 1",
+    ];
+    for fen in &fens {
+        let game = ChessGame::from_fen(fen).unwrap();
+        let moves = game.legal_moves();
+        let mut names: Vec<String> = moves.iter().map(|mv| game.san(mv)).collect();
+        for (mv, san) in moves.iter().zip(&names) {
+            assert_eq!(game.parse_san(san).as_ref(), Ok(mv), "{} in {}", san, fen);
+        }
+        names.sort();
+        names.dedup();
+        assert_eq!(names.len(), moves.len(), "duplicate SAN in {}", fen);
+    }
+}
+
+#[test]
+fn disambiguation() {
+    let game = ChessGame::from_fen("k7/8/8/8/8/4R3/8/4R1K1 w - - 0 1").unwrap();
+    assert_eq!(game.move_san(7, 4, 6, 4, None).unwrap(), "R1e2");
+    assert_eq!(game.move_san(5, 4, 6, 4, None).unwrap(), "R3e2");
+    assert_eq!(game.parse_san("Re2"), Err(SanError::Ambiguous("Re2".to_string())));
+
+    let game = ChessGame::from_fen("rnbqkbnr/pppppppp/8/8/8/5N2/PPP1PPPP/RNBQKB1R w KQkq - 0 1").unwrap();
+    assert_eq!(game.move_san(7, 1, 6, 3, None).unwrap(), "Nbd2");
+    assert_eq!(game.move_san(5, 5, 6, 3, None).unwrap(), "Nfd2");
+
+    // Queens on a8 and a6 share the file, a8 and c8 the rank
+    let game = ChessGame::from_fen("Q1Q5/8/Q7/8/7k/8/8/4K3 w - - 0 1").unwrap();
+    assert_eq!(game.move_san(0, 0, 1, 1, None).unwrap(), "Qa8b7");
+    assert_eq!(game.move_san(0, 2, 1, 1, None).unwrap(), "Qcb7");
+    assert_eq!(game.move_san(2, 0, 1, 1, None).unwrap(), "Q6b7");
+}
+
+#[test]
+fn special_moves() {
+    let game = sans("4k3/3p4/8/4P3/8/8/8/4K3 b - - 0 1", &["d5"]);
+    assert_eq!(game.move_san(3, 4, 2, 3, None).unwrap(), "exd6");
+
+    let game = ChessGame::from_fen("r3k3/8/8/8/8/8/8/4K3 b q - 0 1").unwrap();
+    assert_eq!(game.move_san(0, 4, 0, 2, None).unwrap(), "O-O-O");
+    let game = sans("r3k3/8/8/8/8/8/8/4K3 b q - 0 1", &["O-O-O"]);
+    assert_eq!(game.fen().unwrap(), "2kr4/8/8/8/8/8/8/4K3 w - - 1 2");
+
+    let game = ChessGame::from_fen("7k/4P1pp/8/8/8/8/8/4K3 w - - 0 1").unwrap();
+    assert_eq!(game.move_san(1, 4, 0, 4, Some(5)).unwrap(), "e8=Q#");
+    assert_eq!(game.move_san(1, 4, 0, 4, Some(3)).unwrap(), "e8=N");
+    let game = sans("7k/4P1pp/8/8/8/8/8/4K3 w - - 0 1", &["e8=Q#"]);
+    assert!(game.checkmate());
+
+    let game = sans(
+        "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1",
+        &["e4", "e5", "Bc4", "Nc6", "Qh5", "Nf6"],
+    );
+    assert_eq!(game.move_san(3, 7, 1, 5, None).unwrap(), "Qxf7#");
+    assert_eq!(game.move_san(3, 7, 2, 7, None).unwrap(), "Qh6");
+    assert_eq!(game.move_san(4, 2, 1, 5, None).unwrap(), "Bxf7+");
+}
+
+#[test]
+fn lenient_input_and_clear_errors() {
+    let game = ChessGame::from_fen("7k/4P1pp/8/8/8/8/8/4K3 w - - 0 1").unwrap();
+    assert_eq!(game.parse_san("e8Q"), game.parse_san("e8=Q#"));
+    assert_eq!(game.parse_san("e8=Q+!?"), game.parse_san("e8=Q"));
+    assert_eq!(game.parse_san("e8"), Err(SanError::NoLegalMove("e8".to_string())));
+
+    let game = ChessGame::new();
+    for bad in &["", "Zf3", "N", "Nf9", "e2e4x", "e24"] {
+        assert_eq!(game.parse_san(bad), Err(SanError::Malformed(bad.to_string())));
+    }
+    assert_eq!(game.parse_san("Ke2"), Err(SanError::NoLegalMove("Ke2".to_string())));
+    assert_eq!(game.parse_san("0-0"), Err(SanError::NoLegalMove("0-0".to_string())));
+
+    let mut game = ChessGame::new();
+    assert!(matches!(game.play_san("Nf4"), Err(ChessError::InvalidSan(SanError::NoLegalMove(_)))));
+    assert!(matches!(game.move_san(6, 4, 3, 4, None), Err(ChessError::IllegalMove { .. })));
+}
@@ -0,0 +1,91 @@
+use chessgame::{ChessError, ChessGame, Color, PositionProblem, Square};
+
+const WHITE: i32 = 1;
+const BLACK: i32 = 2;
+const W_KING: i32 = 6;
+const B_KING: i32 = 12;
+const B_PAWN: i32 = 7;
+const W_ROOK: i32 = 2;
+
+#[test]
+fn building_a_position() {
+    let mut game = ChessGame::new();
+    game.clear_board();
+    assert_eq!(game.validate_position(), [PositionProblem::MissingKing(Color::White), PositionProblem::MissingKing(Color::Black)]);
+
+    game.put_piece(7, 4, W_KING).unwrap();
+    game.put_piece(7, 7, W_ROOK).unwrap();
+    game.put_piece(0, 4, B_KING).unwrap();
+    game.put_piece(3, 3, B_PAWN).unwrap();
+    game.set_castling(WHITE, true, true).unwrap();
+    game.set_turn(WHITE).unwrap();
+    assert_eq!(game.validate_position(), []);
+    assert_eq!(game.fen().unwrap(), "4k3/8/8/3p4/8/8/8/4K2R w K - 0 1");
+    assert_eq!(game.validate_position_json(), "[]");
+
+    // A double push that just happened
+    game.set_en_passant(2, 3).unwrap();
+    assert_eq!(game.validate_position(), []);
+    assert_eq!(game.fen().unwrap(), "4k3/8/8/3p4/8/8/8/4K2R w K d6 0 1");
+    game.clear_en_passant();
+    // Without the pawn that passed over it, the square goes too
+    game.set_en_passant(2, 3).unwrap();
+    assert_eq!(game.remove_piece(3, 3).unwrap(), Some(B_PAWN));
+    assert_eq!(game.remove_piece(3, 3).unwrap(), None);
+    assert_eq!(game.validate_position(), []);
+    assert_eq!(game.fen().unwrap(), "4k3/8/8/8/8/8/8/4K2R w K - 0 1");
+
+    assert_eq!(game.put_piece(8, 0, W_KING), Err(ChessError::OffBoard { x: 8, y: 0 }));
+    assert_eq!(game.put_piece(0, 0, 13), Err(ChessError::UnknownPiece(13)));
+    assert_eq!(game.set_turn(3), Err(ChessError::UnknownColor(3)));
+
+    game.play_san("O-O").unwrap();
+    assert_eq!(game.fen().unwrap(), "4k3/8/8/8/8/8/8/5RK1 b - - 1 1");
+}
+
+#[test]
+fn every_problem_is_listed() {
+    let mut game = ChessGame::from_fen("KK2k3/8/2n5/8/8/8/8/P3R3 w KQkq - 0 1").unwrap();
+    game.set_turn(BLACK).unwrap();
+    let problems = game.validate_position();
+    assert_eq!(
+        problems,
+        [
+            PositionProblem::TooManyKings(Color::White),
+            PositionProblem::PawnOnBackRank(Square::new(7, 0).unwrap()),
+            PositionProblem::OpponentInCheck,
+            PositionProblem::ImpossibleCastling { color: Color::White, kingside: true },
+            PositionProblem::ImpossibleCastling { color: Color::White, kingside: false },
+            PositionProblem::ImpossibleCastling { color: Color::Black, kingside: true },
+            PositionProblem::ImpossibleCastling { color: Color::Black, kingside: false },
+        ]
+    );
+    assert!(game.validate_position_json().starts_with(r#"[{"problem":"too_many_kings","square":null,"message":"White has more than one king."}"#));
+
+    let game = ChessGame::from_fen("4k3/8/8/8/8/8/PPPPPPPP/PPPPK3 w - - 0 1").unwrap();
+    assert!(game.validate_position().contains(&PositionProblem::TooManyPawns(Color::White)));
+}
+
+#[test]
+fn no_en_passant_without_a_double_push() {
+    let mut game = ChessGame::from_fen("4k3/8/8/4P3/8/8/8/4K3 w - - 0 1").unwrap();
+    let target = Square::new(2, 3).unwrap();
+    assert_eq!(game.set_en_passant(2, 3), Err(ChessError::ImpossibleEnPassant(target)));
+    assert!(!game.legal_moves().iter().any(|mv| mv.to == target));
+    assert_eq!(game.set_en_passant(9, 3), Err(ChessError::OffBoard { x: 9, y: 3 }));
+
+    // Once the turn passes to the side that pushed, the square no longer fits
+    let mut game = ChessGame::from_fen("4k3/8/8/3pP3/8/8/8/4K3 w - d6 0 1").unwrap();
+    game.set_turn(BLACK).unwrap();
+    game.set_turn(WHITE).unwrap();
+    assert_eq!(game.fen().unwrap(), "4k3/8/8/3pP3/8/8/8/4K3 w - - 0 1");
+}
+
+#[test]
+fn editing_starts_a_new_game() {
+    let mut game = ChessGame::new();
+    game.play_san("e4").unwrap();
+    game.remove_piece(0, 3).unwrap();
+    assert!(!game.undo());
+    assert_eq!(game.fen().unwrap(), "rnb1kbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq e3 0 1");
+}
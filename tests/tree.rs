@@ -0,0 +1,69 @@
+use chessgame::{ChessGame, GameTree};
+
+const WITH_VARIATIONS: &str = "1. e4 e5 2. Nf3 (2. f4 exf4 (2... d5 3. exd5) 3. Nf3) (2. Bc4) 2... Nc6 3. Bb5 *";
+
+#[test]
+fn branching_and_coming_back() {
+    let mut tree = GameTree::new(ChessGame::new());
+    tree.play_san("e4").unwrap();
+    let e5 = tree.play_san("e5").unwrap();
+    let nf3 = tree.play_san("Nf3").unwrap();
+    assert!(tree.back());
+    let f4 = tree.play_san("f4").unwrap();
+    assert_eq!(tree.children_of(e5), [nf3, f4]);
+    assert_eq!(tree.fen().unwrap(), "rnbqkbnr/pppp1ppp/8/4p3/4PP2/8/PPPP2PP/RNBQKBNR b KQkq f3 0 2");
+    assert_eq!(tree.game().ply_count(), 3);
+
+    // Playing a move that is already there follows it
+    assert!(tree.goto(e5));
+    assert_eq!(tree.play_san("Nf3").unwrap(), nf3);
+    assert!(!tree.forward() && tree.current() == nf3);
+
+    assert!(tree.goto(0));
+    while tree.forward() {}
+    assert_eq!(tree.current(), nf3);
+    assert_eq!(tree.mainline(), tree.path_to(nf3));
+    assert_eq!(tree.san_of(f4).as_deref(), Some("f4"));
+    assert!(tree.play_san("Ke3").is_err());
+    assert!(!tree.goto(99));
+}
+
+#[test]
+fn promote_and_delete() {
+    let mut tree = GameTree::from_pgn(WITH_VARIATIONS).unwrap();
+    let e5 = tree.mainline()[2];
+    let (nf3, f4, bc4) = match tree.children_of(e5)[..] {
+        [nf3, f4, bc4] => (nf3, f4, bc4),
+        ref other => panic!("{:?}", other),
+    };
+    let d5 = tree.children_of(f4)[1];
+    let exd5 = tree.children_of(d5)[0];
+
+    assert!(tree.promote_variation(exd5));
+    assert_eq!(tree.children_of(e5), [f4, nf3, bc4]);
+    assert_eq!(tree.children_of(f4)[0], d5);
+    assert_eq!(*tree.mainline().last().unwrap(), exd5);
+    assert!(!tree.promote_variation(0));
+
+    assert!(tree.goto(exd5));
+    assert!(tree.delete_variation(f4));
+    assert_eq!(tree.current(), e5);
+    assert_eq!(tree.children_of(e5), [nf3, bc4]);
+    assert!(tree.node(d5).is_none() && !tree.goto(exd5));
+    assert!(!tree.delete_variation(f4));
+    assert!(!tree.delete_variation(0));
+}
+
+#[test]
+fn pgn_variations_round_trip() {
+    let tree = GameTree::from_pgn(WITH_VARIATIONS).unwrap();
+    let pgn = tree.pgn(&[]);
+    let movetext = pgn.split("\n\n").nth(1).unwrap();
+    assert_eq!(movetext.trim_end().replace('\n', " "), WITH_VARIATIONS);
+    assert_eq!(GameTree::from_pgn(&pgn).unwrap().pgn(&[]), pgn);
+
+    // A black variation and a custom start
+    let pgn = "[SetUp \"1\"]\n[FEN \"r3k3/8/8/8/8/8/8/4K3 b q - 0 12\"]\n\n12... O-O-O (12... Kd7 13. Kd2) 13. Ke2 *";
+    let tree = GameTree::from_pgn(pgn).unwrap();
+    assert!(tree.pgn(&[]).ends_with("\n\n12... O-O-O (12... Kd7 13. Kd2) 13. Ke2 *\n"));
+}
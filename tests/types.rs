@@ -0,0 +1,37 @@
+use chessgame::{ChessGame, Color, Piece, PieceKind, Square};
+
+#[test]
+fn squares_round_trip_through_algebraic_names() {
+    let e4 = Square::from_algebraic("e4").unwrap();
+    assert_eq!((e4.x(), e4.y()), (4, 4));
+    assert_eq!(e4.to_string(), "e4");
+    assert_eq!(Square::from_algebraic("a8"), Square::new(0, 0));
+    assert_eq!(Square::from_algebraic("h1"), Square::new(7, 7));
+
+    for name in ["", "e", "e9", "i1", "e44", "E4"] {
+        assert_eq!(Square::from_algebraic(name), None, "{:?}", name);
+    }
+    assert_eq!(Square::new(8, 0), None);
+}
+
+#[test]
+fn piece_codes_match_the_js_encoding() {
+    for code in 1..=12 {
+        assert_eq!(Piece::from_code(code).unwrap().code(), code);
+    }
+    assert_eq!(Piece::from_code(0), None);
+    assert_eq!(Piece::from_code(13), None);
+    assert_eq!(Piece::from_code(7), Some(Piece::new(Color::Black, PieceKind::Pawn)));
+    assert_eq!(Piece::from_fen_char('n'), Some(Piece::new(Color::Black, PieceKind::Knight)));
+    assert_eq!(Color::from_code(Color::White.code()), Some(Color::White));
+}
+
+#[test]
+fn the_game_exposes_typed_pieces() {
+    let game = ChessGame::new();
+    let e1 = Square::from_algebraic("e1").unwrap();
+    assert_eq!(game.piece_at(e1), Some(Piece::new(Color::White, PieceKind::King)));
+    assert_eq!(game.get_piece(e1.x(), e1.y()), Piece::new(Color::White, PieceKind::King).code());
+    assert_eq!(game.piece_at(Square::from_algebraic("e4").unwrap()), None);
+    assert_eq!(game.turn(), Color::White);
+}
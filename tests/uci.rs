@@ -0,0 +1,43 @@
+use chessgame::{ChessError, ChessGame, IllegalMoveReason, PromotionError};
+
+#[test]
+fn special_moves_in_uci() {
+    let mut game = ChessGame::from_fen("r3k2r/1P6/8/8/5p2/8/4P3/R3K2R w KQkq - 0 1").unwrap();
+    for uci in &["e2e4", "f4e3", "e1c1", "e8g8", "b7a8r"] {
+        game.make_move_uci(uci).unwrap();
+        assert_eq!(game.last_move_uci().as_deref(), Some(*uci));
+    }
+    assert_eq!(game.fen().unwrap(), "R4rk1/8/8/8/8/4p3/8/2KR3R b - - 0 3");
+
+    let history = game.history();
+    assert!(history[1].is_en_passant);
+    assert!(history[2].is_castle && history[3].is_castle);
+    let names: Vec<String> = history.iter().map(|mv| game.to_uci(mv)).collect();
+    assert_eq!(names, ["e2e4", "f4e3", "e1c1", "e8g8", "b7a8r"]);
+}
+
+#[test]
+fn every_legal_move_round_trips_through_uci() {
+    let game = ChessGame::from_fen("r3k2r/Pppp1ppp/1b3nbN/nP6/BBP1P3/q4N2/Pp1P2PP/R2Q1RK1 w kq - 0 1").unwrap();
+    for mv in game.legal_moves() {
+        assert_eq!(game.parse_uci(&game.to_uci(&mv)), Ok(mv));
+    }
+}
+
+#[test]
+fn bad_uci_is_rejected() {
+    let mut game = ChessGame::new();
+    for bad in &["", "e2", "e2e9", "e2e4x", "e2e4qq", "E2E4", "é2e4"] {
+        assert_eq!(game.make_move_uci(bad), Err(ChessError::InvalidUci(bad.to_string())));
+    }
+    assert!(matches!(
+        game.make_move_uci("e2e5"),
+        Err(ChessError::IllegalMove { reason: IllegalMoveReason::WrongShape(_), .. })
+    ));
+    assert_eq!(game.make_move_uci("e2e4q"), Err(ChessError::InvalidPromotion(PromotionError::NotAPromotion)));
+    assert_eq!(game.last_move_uci(), None);
+
+    let mut game = ChessGame::from_fen("8/4P1k1/8/8/8/8/6K1/8 w - - 0 1").unwrap();
+    assert_eq!(game.make_move_uci("e7e8"), Err(ChessError::InvalidPromotion(PromotionError::MissingPiece)));
+    game.make_move_uci("e7e8n").unwrap();
+}
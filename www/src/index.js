@@ -6,7 +6,7 @@ import {
     W_PAWN, W_ROOK, W_KNIGHT, W_BISHOP, W_QUEEN, W_KING,
     pieceImageMap
 } from "./pieces.js";
-import { showPromotionDialog, hidePromotionDialog, setOnPromotionCompleted, setPromotionGameAndConstants } from "./promotion.js";
+import { showPromotionDialog, hidePromotionDialog, setOnPromotionCompleted, setOnPromotionCancelled, setPromotionGameAndConstants } from "./promotion.js";
 import { loadAllSounds, play } from "./sound.js";
 const API_BASE_URL = "/api";
 let chessgame = null;
@@ -14,7 +14,7 @@ let currentGameID = null;
 let myPlayerColor = null; // ('white' or 'black')
 let isProcessingMove = false;
 let selectedPiece = null; // {row, col}
-let possibleMoves = []; // Array of {row, col}
+let possibleMoves = []; // Array of {row, col, capture, castle, promotion, check, ...} from get_moves_json
 let moveHistory = []; // Array of {move: string, notation: string, player: string, timestamp: Date}
 let moveNumber = 1;
 let wasUserInCheck = false;
@@ -56,18 +56,23 @@ async function initializePage() {
         switch (messageData.type) {
             case 'gameStateUpdate':
                 if (messageData.payload && messageData.payload.newFEN) {
+                    // fen() throws while our own promotion waits for its piece. The server can't
+                    // have moved for us meanwhile, and the next update carries the whole position.
+                    if (chessgame.has_pending_promotion()) {
+                        break;
+                    }
                     // Only update if the FEN is different to avoid redundant redraws
                     if (chessgame.fen() !== messageData.payload.newFEN) {
                         console.log("Applying game state update from server.");
                         
                         // Detect if this was a capture move by checking the move notation
-                        const moveNotation = messageData.payload.move || '';
+                        const moveNotation = messageData.payload.move ? moveToSan(messageData.payload.move) : '';
                         const isCapture = moveNotation.includes('x');
                         
                         chessgame.load_fen(messageData.payload.newFEN);
                         
                         if (messageData.payload.move) {
-                            addMoveToHistory(messageData.payload.move, messageData.payload.player);
+                            addMoveToHistory(messageData.payload.move, moveNotation, messageData.payload.player);
                         }
                         
                         if (isCapture) {
@@ -209,8 +214,13 @@ function setupWasmAndListeners() {
         B_QUEEN, B_ROOK, B_KNIGHT, B_BISHOP
     });
 
-    setOnPromotionCompleted((startRow, startCol, endRow, endCol, promotionChar) => {
-        syncMoveWithServer(startRow, startCol, endRow, endCol, promotionChar);
+    setOnPromotionCompleted(() => {
+        syncMoveWithServer();
+    });
+
+    setOnPromotionCancelled(() => {
+        isProcessingMove = false;
+        drawChessboard();
     });
 
     setOnNotificationClose(() => { window.location.href = '/lobby.html'; });
@@ -273,9 +283,9 @@ function drawChessboard() {
             }
             
             // Handle possible moves
-            if (possibleMoves.some(move => move.row === row && move.col === col)) {
-                const isCapture = boardData[row * 8 + col] !== 0;
-                square.classList.add(isCapture ? 'possible-capture' : 'possible-move');
+            const possibleMove = possibleMoves.find(move => move.row === row && move.col === col);
+            if (possibleMove) {
+                square.classList.add(possibleMove.capture ? 'possible-capture' : 'possible-move');
                 cursorStyle = "pointer";
             }
 
@@ -304,12 +314,6 @@ function drawChessboard() {
     warnKingCheck();
 }
 
-function coordsToAlgebraic(startRow, startCol, endRow, endCol) {
-    const files = ['a', 'b', 'c', 'd', 'e', 'f', 'g', 'h'];
-    const ranks = ['8', '7', '6', '5', '4', '3', '2', '1'];
-    return `${files[startCol]}${ranks[startRow]}${files[endCol]}${ranks[endRow]}`;
-}
-
 async function onSquareClick(event) {
     if (isProcessingMove || !currentGameID || !promotionDialog.classList.contains("hidden")) {
         return;
@@ -329,22 +333,25 @@ async function onSquareClick(event) {
         const startRow = selectedPiece.row;
         const startCol = selectedPiece.col;
 
-        if (possibleMoves.some(move => move.row === row && move.col === col)) {
+        const chosenMove = possibleMoves.find(move => move.row === row && move.col === col);
+        if (chosenMove) {
             isProcessingMove = true;
             const movingPiece = { startRow, startCol, endRow: row, endCol: col };
             selectedPiece = null;
             possibleMoves = [];
             
             try {
-                const targetPiece = chessgame.get_piece(movingPiece.endRow, movingPiece.endCol);
-                const isCapture = targetPiece !== 0;
-                
                 const isPromotion = await chessgame.make_move(movingPiece.startRow, movingPiece.startCol, movingPiece.endRow, movingPiece.endCol);
                 
-                if (isCapture) {
-                    play("capture");
-                } else {
-                    play("move");
+                // The promotion dialog plays its own sound once the piece is chosen
+                if (!chosenMove.promotion) {
+                    if (chosenMove.check) {
+                        play("check");
+                    } else if (chosenMove.capture) {
+                        play("capture");
+                    } else {
+                        play("move");
+                    }
                 }
                 
                 // animation, does this even work???? maybe not working due to the chessboard being 
@@ -367,7 +374,7 @@ async function onSquareClick(event) {
                 if (isPromotion) {
                     showPromotionDialog(movingPiece.startRow, movingPiece.startCol, movingPiece.endRow, movingPiece.endCol);
                 } else {
-                    await syncMoveWithServer(movingPiece.startRow, movingPiece.startCol, movingPiece.endRow, movingPiece.endCol);
+                    await syncMoveWithServer();
                 }
             } catch (error) {
                 console.error("WASM move validation failed:", error);
@@ -389,8 +396,9 @@ async function onSquareClick(event) {
     }
 }
 
-async function syncMoveWithServer(startRow, startCol, endRow, endCol, promotionChar = '') {
-    const moveStr = coordsToAlgebraic(startRow, startCol, endRow, endCol) + promotionChar;
+// Sends the move just played locally, in the UCI notation both sides validate
+async function syncMoveWithServer() {
+    const moveStr = chessgame.last_move_uci();
     
     try {
         const response = await fetch(`${API_BASE_URL}/game/${currentGameID}/move`, {
@@ -418,13 +426,10 @@ async function syncMoveWithServer(startRow, startCol, endRow, endCol, promotionC
 
 function handlePieceSelection(row, col) {
     selectedPiece = { row, col };
-    const movesArray = chessgame.get_moves(row, col);
-    possibleMoves = [];
-    if (movesArray && typeof movesArray.length === 'number' && movesArray.length % 2 === 0) {
-        for (let i = 0; i < movesArray.length; i += 2) {
-            possibleMoves.push({ row: movesArray[i], col: movesArray[i + 1] });
-        }
-    }
+    const targets = JSON.parse(chessgame.get_moves_json(row, col));
+    possibleMoves = Array.isArray(targets)
+        ? targets.map(({ to: [targetRow, targetCol], ...flags }) => ({ row: targetRow, col: targetCol, ...flags }))
+        : [];
     drawChessboard();
 }
 
@@ -451,61 +456,40 @@ function warnKingCheck() {
 }
 
 function checkGameEndConditions() {
-    if (chessgame.is_stalemate()) {
+    const status = JSON.parse(chessgame.status_json());
+    if (status.outcome === "1/2-1/2") {
         showNotification("draw");
-    } else if (chessgame.checkmate()) {
-        const winnerPlayer = chessgame.get_current_turn() === BLACK ? "White" : "Black";
+    } else if (status.winner) {
+        const winnerPlayer = status.winner === "white" ? "White" : "Black";
         showNotification(winnerPlayer);
     }
 }
 
-function convertToAlgebraicNotation(moveStr, player) {
-    // Simple conversion from coordinate notation to algebraic notation
-    // This is a basic implementation - could be enhanced for castling, en passant, etc.
+// Must run before the move is applied to chessgame
+function moveToSan(moveStr) {
     const files = ['a', 'b', 'c', 'd', 'e', 'f', 'g', 'h'];
-    const pieces = {
-        1: 'P', 2: 'N', 3: 'B', 4: 'R', 5: 'Q', 6: 'K', // White pieces
-        9: 'p', 10: 'n', 11: 'b', 12: 'r', 13: 'q', 14: 'k' // Black pieces
-    };
-    
     if (moveStr.length < 4) return moveStr;
-    
+
     const startCol = files.indexOf(moveStr[0]);
     const startRow = 8 - parseInt(moveStr[1]);
     const endCol = files.indexOf(moveStr[2]);
     const endRow = 8 - parseInt(moveStr[3]);
-    
-    const startPiece = chessgame.get_piece(startRow, startCol);
-    const endPiece = chessgame.get_piece(endRow, endCol);
-    const pieceSymbol = pieces[startPiece] || '';
-    
-    let notation = pieceSymbol;
-    if (pieceSymbol === 'P' || pieceSymbol === 'p') {
-        notation = ''; 
-    }
-    
-    if (endPiece !== 0) {
-        if (notation === '') {
-            notation = moveStr[0];
-        }
-        notation += 'x';
+
+    let promotion;
+    if (moveStr.length > 4) {
+        const pieceType = { r: 2, n: 3, b: 4, q: 5 }[moveStr[4]];
+        promotion = chessgame.get_current_turn() === WHITE ? pieceType : pieceType + 6;
     }
-    
-    notation += moveStr[2] + moveStr[3];
-    
-    if (chessgame.check()) {
-        if (chessgame.checkmate()) {
-            notation += '#';
-        } else {
-            notation += '+';
-        }
+
+    try {
+        return chessgame.move_san(startRow, startCol, endRow, endCol, promotion);
+    } catch (error) {
+        console.warn(`Could not convert ${moveStr} to SAN:`, error);
+        return moveStr;
     }
-    
-    return notation;
 }
 
-function addMoveToHistory(moveStr, player) {
-    const notation = convertToAlgebraicNotation(moveStr, player);
+function addMoveToHistory(moveStr, notation, player) {
     const moveEntry = {
         move: moveStr,
         notation: notation,
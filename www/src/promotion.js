@@ -5,6 +5,7 @@ let _chessgame = null;
 let _promotionConstants = {};
 let promotionInfo = null; //  stores { startX, startY, endX, endY }
 let onPromotionCompletedCallback = null;
+let onPromotionCancelledCallback = null;
 const TRANSITION_DURATION = 300; 
 
 const promotionOverlay = document.getElementById("promotion-overlay");
@@ -24,6 +25,10 @@ export function setOnPromotionCompleted(callback) {
     onPromotionCompletedCallback = callback;
 }
 
+export function setOnPromotionCancelled(callback) {
+    onPromotionCancelledCallback = callback;
+}
+
 export async function onPromotionChoice(event) {
     const chosenPieceValue = parseInt(event.currentTarget.dataset.pieceValue);
 
@@ -34,42 +39,34 @@ export async function onPromotionChoice(event) {
     }
     
     // Deconstruct for clarity
-    const { startX, startY, endX, endY } = promotionInfo;
+    const { endX, endY } = promotionInfo;
 
     try {
         if (!_chessgame) throw new Error("Chess game instance not set.");
         
+        // Completes the held-back pawn move and passes the turn
         await _chessgame.promote_pawn(endX, endY, chosenPieceValue);
-        _chessgame.change_turn();
 
         hidePromotionDialog();
 
         if (onPromotionCompletedCallback) {
-            // Convert chosen piece to a character for the server
-            let promotionChar = '';
-            const pieceType = (chosenPieceValue - 1) % 6 + 1;
-            switch (pieceType) {
-                case 5: promotionChar = 'q'; break; // Queen
-                case 2: promotionChar = 'r'; break; // Rook
-                case 4: promotionChar = 'b'; break; // Bishop
-                case 3: promotionChar = 'n'; break; // Knight
-            }
-            // callback with all info needed to sync with the server
             try {
                 await loadAllSounds();
                 play("promote");
             } catch (e) {
                 console.warn("Failed to play promote sound:", e);
             }
-            onPromotionCompletedCallback(startX, startY, endX, endY, promotionChar);
+            // The game now holds the finished move, so the callback can read it from there
+            onPromotionCompletedCallback();
         }
 
     } catch (error) {
         console.error("Error promoting pawn:", error);
         alert(`Promotion failed: ${error.message || error}`);
+        // Nothing was played, so drop the held-back pawn move and send nothing
+        if (_chessgame) _chessgame.cancel_promotion();
         hidePromotionDialog();
-        // Redraw to revert to pre-promotion state
-        if (onPromotionCompletedCallback) onPromotionCompletedCallback();
+        if (onPromotionCancelledCallback) onPromotionCancelledCallback();
     }
 }
 